@@ -1,105 +1,311 @@
-use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderValue};
-use tokio_retry::{
-    strategy::{jitter, ExponentialBackoff},
-    Retry,
-};
+use reqwest::header::{HeaderValue, ACCEPT};
+use reqwest::{ResponseBuilderExt, Url};
+use serde::de::DeserializeOwned;
+use tracing::{debug, instrument};
 
+use crate::cassette::{Cassette, RawResponse, Request};
 use crate::client::Client;
-use crate::error::{EnvVarError, Error};
+use crate::config::{AccessLevel, Config};
+use crate::error::Error;
+use crate::secret::Secret;
 use crate::types::*;
 
-const SEASON_23_24_ID: &str = "sr:season:105353";
+const PREMIER_LEAGUE_ID: &str = "sr:competition:17";
 
-#[cfg(not(test))]
-const SEASON_COMPETITORS_URL: &str = "https://api.sportradar.com/soccer/trial/v4/en/seasons/$SEASON/competitors.json?api_key=$API_KEY";
+// The defaults of the builder and of the config
+pub(crate) const API_URL: &str = "https://api.sportradar.com";
+pub(crate) const ACCESS_LEVEL: AccessLevel = AccessLevel::Trial;
+pub(crate) const API_VERSION: &str = "v4";
+pub(crate) const LOCALE: &str = "en";
 
-#[cfg(not(test))]
-const COMPETITOR_STATS_URL: &str = "https://api.sportradar.com/soccer/trial/v4/en/seasons/$SEASON/competitors/$COMPETITOR/statistics.json?api_key=$API_KEY";
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
 pub struct SportsApiClient {
     client: reqwest::Client,
-    api_key: String,
+    // Sent as a header, as the URL ends up in errors and logs
+    api_key: HeaderValue,
+    base_url: Url,
+    access_level: AccessLevel,
+    api_version: String,
+    locale: String,
+    cassette: Option<Cassette>,
+}
 
-    #[cfg(test)]
-    pub(crate) mock_url: Option<String>,
+// Everything but the API key has a default, pointing to the Sportradar API
+pub struct SportsApiClientBuilder {
+    api_key: Option<Secret>,
+    base_url: Url,
+    access_level: AccessLevel,
+    api_version: String,
+    locale: String,
+    timeout: Option<Duration>,
+    user_agent: String,
+    proxy: Option<reqwest::Proxy>,
+    client: Option<reqwest::Client>,
+    cassette: Option<Cassette>,
 }
 
-impl SportsApiClient {
-    // Requires SPORTRADAR_API_KEY env var
-    pub fn new() -> Result<Self, Error> {
-        let mut headers = HeaderMap::new();
-        headers.insert("accept", HeaderValue::from_static("application/json"));
-
-        Ok(Self {
-            api_key: env::var("SPORTRADAR_API_KEY")
-                .map_err(|e| EnvVarError::new("SPORTRADAR_API_KEY", e))?,
-            client: reqwest::Client::builder()
-                .default_headers(headers)
-                .build()
-                .unwrap(),
-            #[cfg(test)]
-            mock_url: None,
+impl Default for SportsApiClientBuilder {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            base_url: Url::parse(API_URL).expect("should be a valid URL"),
+            access_level: ACCESS_LEVEL,
+            api_version: API_VERSION.into(),
+            locale: LOCALE.into(),
+            timeout: None,
+            user_agent: USER_AGENT.into(),
+            proxy: None,
+            client: None,
+            cassette: None,
+        }
+    }
+}
+
+impl SportsApiClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Takes the API settings of the configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            api_key: config.api_key.clone(),
+            base_url: config.base_url.clone(),
+            access_level: config.access_level,
+            api_version: config.api_version.clone(),
+            locale: config.locale.clone(),
+            ..Self::default()
+        }
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<Secret>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+    // Such as a local stub server, the API paths are appended to it
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = base_url;
+        self
+    }
+    pub fn access_level(mut self, access_level: AccessLevel) -> Self {
+        self.access_level = access_level;
+        self
+    }
+    pub fn api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = locale.into();
+        self
+    }
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+    // Without it, the system proxy from the HTTP(S)_PROXY env vars is used
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+    // Used as is, so the timeout, user agent and proxy are left to it
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    // Records every response into it, or answers every request from it
+    pub(crate) fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    pub fn build(self) -> Result<SportsApiClient, Error> {
+        let api_key = match self.api_key {
+            Some(api_key) => {
+                let mut api_key =
+                    HeaderValue::from_str(api_key.expose()).map_err(|_| Error::InvalidApiKey)?;
+                api_key.set_sensitive(true);
+                api_key
+            }
+            // Never sent, the cassette answers every request
+            None if self.cassette.as_ref().is_some_and(Cassette::replaying) => {
+                HeaderValue::from_static("")
+            }
+            None => return Err(Error::MissingApiKey),
+        };
+
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder().user_agent(self.user_agent);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(SportsApiClient {
+            client,
+            api_key,
+            base_url: self.base_url,
+            access_level: self.access_level,
+            api_version: self.api_version,
+            locale: self.locale,
+            cassette: self.cassette,
         })
     }
+}
 
-    #[cfg(test)]
-    fn set_mock_url(&mut self, url: String) {
-        self.mock_url = Some(url);
+impl SportsApiClient {
+    // Requires the API key to be configured
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        SportsApiClientBuilder::from_config(config).build()
     }
 
-    async fn competitors(&self) -> Result<SeasonCompetitors, Error> {
-        #[cfg(not(test))]
-        let base_url = SEASON_COMPETITORS_URL;
-        #[cfg(test)]
-        let base_url = self.mock_url.as_ref().unwrap();
-        let url = base_url
-            .replace("$SEASON", SEASON_23_24_ID)
-            .replace("$API_KEY", &self.api_key);
+    pub fn builder() -> SportsApiClientBuilder {
+        SportsApiClientBuilder::new()
+    }
 
-        Ok(self
-            .client
-            .get(url)
-            .send()
-            .await?
-            .json::<SeasonCompetitors>()
-            .await?)
-    }
-
-    async fn competitor_stats(&self, id: &str) -> Result<CompetitorStats, Error> {
-        #[cfg(not(test))]
-        let base_url = COMPETITOR_STATS_URL;
-        #[cfg(test)]
-        let base_url = self.mock_url.as_ref().unwrap();
-        let url = base_url
-            .replace("$SEASON", SEASON_23_24_ID)
-            .replace("$COMPETITOR", id)
-            .replace("$API_KEY", &self.api_key);
-
-        Ok(self
+    // Such as "/soccer/trial/v4/en/seasons/<season>/competitors.json",
+    // the segments are percent encoded
+    fn url(&self, path: &[&str]) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base URL should not be a cannot-be-a-base URL")
+            .pop_if_empty()
+            .extend([
+                "soccer",
+                self.access_level.as_str(),
+                &self.api_version,
+                &self.locale,
+            ])
+            .extend(path);
+        url
+    }
+
+    // The responses of a cassette go through the same parsing as the API's
+    #[instrument(level = "debug", skip_all, fields(path = url.path()))]
+    async fn get<T: DeserializeOwned>(&self, request: Request, url: Url) -> Result<T, Error> {
+        let response = match &self.cassette {
+            Some(cassette) if cassette.replaying() => {
+                Self::response(url, cassette.replay_response(&request)?)?
+            }
+            Some(cassette) => {
+                let response = self.send(url.clone()).await?;
+                let raw = RawResponse {
+                    status: response.status().as_u16(),
+                    body: String::from_utf8_lossy(&response.bytes().await?).into_owned(),
+                };
+                cassette.record_response(request, raw.clone())?;
+                Self::response(url, raw)?
+            }
+            None => self.send(url).await?,
+        };
+        Ok(response.error_for_status()?.json::<T>().await?)
+    }
+
+    // Only the path is logged, the API key is sent in a header
+    async fn send(&self, url: Url) -> Result<reqwest::Response, Error> {
+        let start = Instant::now();
+        let response = self
             .client
             .get(url)
+            .header(ACCEPT, "application/json")
+            .header("x-api-key", self.api_key.clone())
             .send()
-            .await?
-            .json::<CompetitorStats>()
-            .await?)
+            .await?;
+        debug!(
+            status = response.status().as_u16(),
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "response"
+        );
+        Ok(response)
+    }
+
+    // As the API sent it, for the URL it was sent to
+    fn response(url: Url, raw: RawResponse) -> Result<reqwest::Response, Error> {
+        let response = http::Response::builder()
+            .status(raw.status)
+            .url(url)
+            .body(raw.body)
+            .map_err(|_| Error::InvalidStatus(raw.status))?;
+        Ok(response.into())
+    }
+
+    async fn seasons(&self) -> Result<CompetitionSeasons, Error> {
+        let url = self.url(&["competitions", PREMIER_LEAGUE_ID, "seasons.json"]);
+        self.get(Request::Seasons, url).await
+    }
+
+    async fn competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        let request = Request::Competitors {
+            season: season.into(),
+        };
+        self.get(request, self.url(&["seasons", season, "competitors.json"]))
+            .await
+    }
+
+    async fn competitor_stats(&self, season: &str, id: &str) -> Result<CompetitorStats, Error> {
+        let request = Request::CompetitorStats {
+            season: season.into(),
+            competitor: id.into(),
+        };
+        let url = self.url(&["seasons", season, "competitors", id, "statistics.json"]);
+        self.get(request, url).await
     }
-}
 
-fn retry_strategy() -> impl Iterator<Item = Duration> {
-    ExponentialBackoff::from_millis(20).map(jitter).take(3)
+    async fn standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        let request = Request::Standings {
+            season: season.into(),
+        };
+        self.get(request, self.url(&["seasons", season, "standings.json"]))
+            .await
+    }
+
+    async fn schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        let request = Request::Schedules {
+            season: season.into(),
+        };
+        self.get(request, self.url(&["seasons", season, "schedules.json"]))
+            .await
+    }
 }
 
+// Every request is made once, retries and rate limiting are layered on top,
+// see the middleware module
 #[async_trait]
 impl Client for SportsApiClient {
-    async fn fetch_competitors(&self) -> Result<SeasonCompetitors, Error> {
-        Retry::spawn(retry_strategy(), || self.competitors()).await
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        self.seasons().await
+    }
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        self.competitors(season).await
+    }
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        self.competitor_stats(season, id).await
     }
-    async fn fetch_competitor_stats(&self, id: &str) -> Result<CompetitorStats, Error> {
-        Retry::spawn(retry_strategy(), || self.competitor_stats(id)).await
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        self.standings(season).await
+    }
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        self.schedules(season).await
     }
 }
 
@@ -107,21 +313,192 @@ impl Client for SportsApiClient {
 mod test {
     use crate::api_client::SportsApiClient;
     use crate::client::Client;
+    use std::error::Error as _;
+
+    use crate::config::{AccessLevel, Config};
+    use crate::error::Error;
     use crate::types::{
-        CompetitorPlayers, CompetitorStats, Player, PlayerStats, SeasonCompetitor,
-        SeasonCompetitors,
+        CompetitionSeasons, Competitor, CompetitorRef, CompetitorStats, MatchCompetitor,
+        MatchStatus, MatchSummary, Player, PlayerStats, Qualifier, Round, Season, SeasonCompetitor,
+        SeasonCompetitors, SeasonSchedules, SeasonStandings, SportEvent, SportEventContext,
+        SportEventStatus, Standing, StandingGroup, StandingRow, StandingType, TeamStats,
     };
 
+    const SEASON_ID: &str = "sr:season:105353";
+
+    fn mock_config() -> Config {
+        Config {
+            api_key: Some("asdf1234".into()),
+            ..Default::default()
+        }
+    }
+
+    fn mock_client(base_url: &str) -> SportsApiClient {
+        SportsApiClient::builder()
+            .api_key("asdf1234")
+            .base_url(base_url.parse().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_url() {
+        let config = Config {
+            access_level: AccessLevel::Production,
+            locale: "pt".into(),
+            ..mock_config()
+        };
+        let client = SportsApiClient::new(&config).unwrap();
+
+        assert_eq!(
+            client
+                .url(&["seasons", SEASON_ID, "competitors.json"])
+                .as_str(),
+            "https://api.sportradar.com/soccer/production/v4/pt/seasons/sr:season:105353/competitors.json"
+        );
+
+        let client = SportsApiClient::builder()
+            .api_key("asdf1234")
+            .base_url("http://localhost:1234/proxy/".parse().unwrap())
+            .access_level(AccessLevel::Production)
+            .locale("pt")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.url(&["seasons", "a/b c", "standings.json"]).as_str(),
+            "http://localhost:1234/proxy/soccer/production/v4/pt/seasons/a%2Fb%20c/standings.json"
+        );
+
+        // the defaults don't depend on the config
+        let client = SportsApiClient::builder()
+            .api_key("asdf1234")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.url(&["seasons.json"]).as_str(),
+            "https://api.sportradar.com/soccer/trial/v4/en/seasons.json"
+        );
+    }
+
+    // Display, Debug and every source of the error
+    fn renderings(err: &Error) -> Vec<String> {
+        let mut renderings = vec![format!("{err}"), format!("{err:?}"), format!("{err:#?}")];
+        let mut source = err.source();
+        while let Some(err) = source {
+            renderings.push(format!("{err}"));
+            source = err.source();
+        }
+        renderings
+    }
+
     #[tokio::test]
-    async fn test_fetch_competitors() {
-        dotenv::from_filename(".env.example").ok();
-        let mut client = SportsApiClient::new().unwrap();
+    async fn test_errors_never_contain_api_key() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(500)
+            .with_body("Internal Server Error")
+            .create_async()
+            .await;
+
+        let client = |base_url: &str| {
+            SportsApiClient::builder()
+                .api_key("asdf1234")
+                .base_url(base_url.parse().unwrap())
+                .build()
+        };
+        let mut errors = vec![];
+
+        errors.push(
+            client(&server.url())
+                .unwrap()
+                .fetch_seasons()
+                .await
+                .unwrap_err(),
+        );
+        // Nothing listens on port 1, so the connection is refused
+        errors.push(
+            client("http://127.0.0.1:1")
+                .unwrap()
+                .fetch_standings(SEASON_ID)
+                .await
+                .unwrap_err(),
+        );
+        errors.push(
+            SportsApiClient::builder()
+                .api_key("asdf1234\n")
+                .build()
+                .err()
+                .unwrap(),
+        );
 
+        assert!(matches!(errors[0], Error::Request(_)));
+        assert!(matches!(errors[1], Error::Request(_)));
+        assert!(matches!(errors[2], Error::InvalidApiKey));
+        for err in &errors {
+            for rendering in renderings(err) {
+                assert!(!rendering.contains("asdf1234"), "{rendering}");
+            }
+        }
+        assert!(!format!("{:?}", mock_config()).contains("asdf1234"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_seasons() {
         let mut server = mockito::Server::new_async().await;
 
-        let base_url = server.url();
-        let route = "/soccer/trial/v4/en/seasons/$SEASON/competitors.json?api_key=$API_KEY";
-        client.set_mock_url(format!("{base_url}{route}"));
+        let client = mock_client(&server.url());
+
+        let json = r###"
+            {
+              "generated_at": "2024-07-01T11:02:31+00:00",
+              "seasons": [
+                {
+                  "id": "sr:season:105353",
+                  "name": "Premier League 23/24",
+                  "start_date": "2023-08-11",
+                  "end_date": "2024-05-19",
+                  "year": "23/24",
+                  "competition_id": "sr:competition:17"
+                }
+              ]
+            }
+        "###;
+
+        let mock = server
+            .mock(
+                "GET",
+                "/soccer/trial/v4/en/competitions/sr:competition:17/seasons.json",
+            )
+            .match_header("x-api-key", "asdf1234")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json)
+            .create_async()
+            .await;
+
+        let seasons = client.fetch_seasons().await.unwrap();
+        mock.assert();
+
+        assert_eq!(
+            seasons,
+            CompetitionSeasons {
+                seasons: vec![Season {
+                    id: "sr:season:105353".into(),
+                    name: "Premier League 23/24".into(),
+                    start_date: "2023-08-11".parse().unwrap(),
+                    end_date: "2024-05-19".parse().unwrap(),
+                    year: "23/24".into(),
+                }],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_competitors() {
+        let mut server = mockito::Server::new_async().await;
+
+        let client = mock_client(&server.url());
 
         let json = r###"
             {
@@ -146,15 +523,16 @@ mod test {
         let mock = server
             .mock(
                 "GET",
-                "/soccer/trial/v4/en/seasons/sr:season:105353/competitors.json?api_key=asdf1234",
+                "/soccer/trial/v4/en/seasons/sr:season:105353/competitors.json",
             )
+            .match_header("x-api-key", "asdf1234")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(json)
             .create_async()
             .await;
 
-        let competitors = client.fetch_competitors().await.unwrap();
+        let competitors = client.fetch_competitors(SEASON_ID).await.unwrap();
         mock.assert();
 
         assert_eq!(
@@ -174,14 +552,9 @@ mod test {
 
     #[tokio::test]
     async fn test_fetch_competitor_stats() {
-        dotenv::from_filename(".env.example").ok();
-        let mut client = SportsApiClient::new().unwrap();
-
         let mut server = mockito::Server::new_async().await;
 
-        let base_url = server.url();
-        let route = "/soccer/trial/v4/en/seasons/$SEASON/competitors/$COMPETITOR/statistics.json?api_key=$API_KEY";
-        client.set_mock_url(format!("{base_url}{route}"));
+        let client = mock_client(&server.url());
 
         let json = r###"
            {
@@ -286,14 +659,15 @@ mod test {
            }
         "###;
 
-        let mock = server.mock("GET", "/soccer/trial/v4/en/seasons/sr:season:105353/competitors/sr:competitor:17/statistics.json?api_key=asdf1234")
+        let mock = server.mock("GET", "/soccer/trial/v4/en/seasons/sr:season:105353/competitors/sr:competitor:17/statistics.json")
+            .match_header("x-api-key", "asdf1234")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(json)
             .create_async().await;
 
         let competitors = client
-            .fetch_competitor_stats("sr:competitor:17")
+            .fetch_competitor_stats(SEASON_ID, "sr:competitor:17")
             .await
             .unwrap();
         mock.assert();
@@ -301,14 +675,47 @@ mod test {
         assert_eq!(
             competitors,
             CompetitorStats {
-                competitor: CompetitorPlayers {
+                competitor: Competitor {
+                    id: "sr:competitor:17".into(),
+                    name: "Manchester City".into(),
+                    abbreviation: "MCI".into(),
+                    statistics: TeamStats {
+                        matches_played: 38,
+                        average_ball_possession: 65.53,
+                        goals_scored: 96,
+                        goals_scored_first_half: 40,
+                        goals_scored_second_half: 56,
+                        goals_conceded: 34,
+                        goals_conceded_first_half: 16,
+                        goals_conceded_second_half: 18,
+                        goals_by_foot: 83,
+                        goals_by_head: 11,
+                        shots_total: 631,
+                        shots_on_target: 261,
+                        shots_off_target: 193,
+                        shots_blocked: 177,
+                        corner_kicks: 286,
+                        cards_given: 55,
+                        yellow_cards: 53,
+                        yellow_red_cards: 1,
+                        red_cards: 1,
+                    },
                     players: vec![
                         Player {
                             id: "sr:player:44614".into(),
                             name: "Walker, Kyle".into(),
                             statistics: PlayerStats {
                                 assists: 4,
-                                goals_scored: 0
+                                goals_scored: 0,
+                                matches_played: 32,
+                                shots_on_target: 3,
+                                shots_off_target: 6,
+                                shots_blocked: 7,
+                                offsides: 7,
+                                yellow_cards: 2,
+                                substituted_in: 2,
+                                substituted_out: 3,
+                                ..Default::default()
                             }
                         },
                         Player {
@@ -316,7 +723,18 @@ mod test {
                             name: "De Bruyne, Kevin".into(),
                             statistics: PlayerStats {
                                 assists: 10,
-                                goals_scored: 4
+                                goals_scored: 4,
+                                matches_played: 18,
+                                goals_by_head: 1,
+                                shots_on_target: 14,
+                                shots_off_target: 13,
+                                shots_blocked: 14,
+                                corner_kicks: 66,
+                                offsides: 1,
+                                yellow_cards: 2,
+                                substituted_in: 3,
+                                substituted_out: 10,
+                                ..Default::default()
                             }
                         }
                     ],
@@ -324,4 +742,207 @@ mod test {
             }
         );
     }
+
+    #[tokio::test]
+    async fn test_fetch_standings() {
+        let mut server = mockito::Server::new_async().await;
+
+        let client = mock_client(&server.url());
+
+        let json = r###"
+            {
+              "generated_at": "2024-07-01T10:12:43+00:00",
+              "standings": [
+                {
+                  "tie_break_rule": "In the event that two (or more) teams have an equal number of points...",
+                  "type": "total",
+                  "groups": [
+                    {
+                      "id": "sr:league:76217",
+                      "live": false,
+                      "name": "Premier League 23/24",
+                      "standings": [
+                        {
+                          "rank": 1,
+                          "played": 38,
+                          "win": 28,
+                          "loss": 3,
+                          "draw": 7,
+                          "goals_for": 96,
+                          "goals_against": 34,
+                          "goals_diff": 62,
+                          "points": 91,
+                          "change": 0,
+                          "current_outcome": "Champions League",
+                          "competitor": {
+                            "id": "sr:competitor:17",
+                            "name": "Manchester City",
+                            "country": "England",
+                            "country_code": "ENG",
+                            "abbreviation": "MCI",
+                            "gender": "male"
+                          }
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "type": "first_half_total",
+                  "groups": []
+                }
+              ]
+            }
+        "###;
+
+        let mock = server
+            .mock(
+                "GET",
+                "/soccer/trial/v4/en/seasons/sr:season:105353/standings.json",
+            )
+            .match_header("x-api-key", "asdf1234")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json)
+            .create_async()
+            .await;
+
+        let standings = client.fetch_standings(SEASON_ID).await.unwrap();
+        mock.assert();
+
+        assert_eq!(
+            standings,
+            SeasonStandings {
+                standings: vec![
+                    Standing {
+                        kind: StandingType::Total,
+                        groups: vec![StandingGroup {
+                            standings: vec![StandingRow {
+                                rank: 1,
+                                competitor: CompetitorRef {
+                                    id: "sr:competitor:17".into(),
+                                    name: "Manchester City".into(),
+                                    abbreviation: "MCI".into(),
+                                },
+                                played: 38,
+                                win: 28,
+                                draw: 7,
+                                loss: 3,
+                                goals_for: 96,
+                                goals_against: 34,
+                                goals_diff: 62,
+                                points: 91,
+                            }],
+                        }],
+                    },
+                    Standing {
+                        kind: StandingType::Other,
+                        groups: vec![],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_schedules() {
+        let mut server = mockito::Server::new_async().await;
+
+        let client = mock_client(&server.url());
+
+        let json = r###"
+            {
+              "generated_at": "2024-07-01T10:40:02+00:00",
+              "schedules": [
+                {
+                  "sport_event": {
+                    "id": "sr:sport_event:41762823",
+                    "start_time": "2023-08-11T19:00:00+00:00",
+                    "start_time_confirmed": true,
+                    "sport_event_context": {
+                      "sport": { "id": "sr:sport:1", "name": "Soccer" },
+                      "round": { "number": 1 }
+                    },
+                    "competitors": [
+                      {
+                        "id": "sr:competitor:6",
+                        "name": "Burnley FC",
+                        "country": "England",
+                        "country_code": "ENG",
+                        "abbreviation": "BUR",
+                        "qualifier": "home",
+                        "gender": "male"
+                      },
+                      {
+                        "id": "sr:competitor:17",
+                        "name": "Manchester City",
+                        "country": "England",
+                        "country_code": "ENG",
+                        "abbreviation": "MCI",
+                        "qualifier": "away",
+                        "gender": "male"
+                      }
+                    ]
+                  },
+                  "sport_event_status": {
+                    "status": "closed",
+                    "match_status": "ended",
+                    "home_score": 0,
+                    "away_score": 3,
+                    "winner_id": "sr:competitor:17"
+                  }
+                }
+              ]
+            }
+        "###;
+
+        let mock = server
+            .mock(
+                "GET",
+                "/soccer/trial/v4/en/seasons/sr:season:105353/schedules.json",
+            )
+            .match_header("x-api-key", "asdf1234")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json)
+            .create_async()
+            .await;
+
+        let schedules = client.fetch_schedules(SEASON_ID).await.unwrap();
+        mock.assert();
+
+        assert_eq!(
+            schedules,
+            SeasonSchedules {
+                schedules: vec![MatchSummary {
+                    sport_event: SportEvent {
+                        id: "sr:sport_event:41762823".into(),
+                        start_time: "2023-08-11T19:00:00Z".parse().unwrap(),
+                        sport_event_context: SportEventContext {
+                            round: Some(Round { number: Some(1) }),
+                        },
+                        competitors: vec![
+                            MatchCompetitor {
+                                id: "sr:competitor:6".into(),
+                                name: "Burnley FC".into(),
+                                abbreviation: "BUR".into(),
+                                qualifier: Some(Qualifier::Home),
+                            },
+                            MatchCompetitor {
+                                id: "sr:competitor:17".into(),
+                                name: "Manchester City".into(),
+                                abbreviation: "MCI".into(),
+                                qualifier: Some(Qualifier::Away),
+                            },
+                        ],
+                    },
+                    sport_event_status: SportEventStatus {
+                        status: MatchStatus::Closed,
+                        home_score: Some(0),
+                        away_score: Some(3),
+                    },
+                }],
+            }
+        );
+    }
 }
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+
+use matchday::mock_server::{MockServer, MockServerOptions};
+
+/// Stand-in for the Sportradar API, serving fixture files for development and end-to-end tests.
+/// Point matchday to it with `--base-url http://localhost:<port>`.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Port to listen on
+    #[arg(long, default_value_t = 4000)]
+    port: u16,
+    /// Folder with the fixture files
+    #[arg(long, default_value = "fixtures")]
+    fixtures: PathBuf,
+    /// Delay before every response, in milliseconds
+    #[arg(long, default_value_t = 0)]
+    latency_ms: u64,
+    /// Answers every nth request with 429 Too Many Requests
+    #[arg(long)]
+    rate_limit_every: Option<usize>,
+    /// Answers every nth request with 503 Service Unavailable
+    #[arg(long)]
+    server_error_every: Option<usize>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let options = MockServerOptions {
+        fixtures: args.fixtures,
+        latency: Duration::from_millis(args.latency_ms),
+        rate_limit_every: args.rate_limit_every,
+        server_error_every: args.server_error_every,
+    };
+    let mut server = MockServer::bind(([127, 0, 0, 1], args.port).into(), options).await?;
+    eprintln!("Serving fixtures on {}", server.url());
+
+    server.wait().await;
+
+    Ok(())
+}
@@ -2,194 +2,799 @@ use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, instrument, trace};
 
-use crate::api_client::SportsApiClient;
 use crate::client::Client;
+use crate::config::Config;
+use crate::db::DATABASE_FILE;
 use crate::error::{Error, IOError, JSONError};
-use crate::types::{CompetitorStats, SeasonCompetitors};
+use crate::metrics::Metrics;
+use crate::quota::LEDGER_FILE;
+use crate::snapshot::SNAPSHOTS_FOLDER;
+use crate::types::{
+    CompetitionSeasons, CompetitorStats, MatchSummary, SeasonCompetitors, SeasonSchedules,
+    SeasonStandings,
+};
 
-#[cfg(not(test))]
-const CACHE_FOLDER: &str = ".matchday";
-#[cfg(test)]
-pub(crate) const CACHE_FOLDER: &str = ".tmp-cache-matchday";
+const PREMIER_LEAGUE_TEAMS: usize = 20;
+
+// The seasons and standings change during a season, so with the config they
+// expire after a day even without a TTL
+const LISTINGS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Files in the cache dir that aren't cached data, so clearing it keeps them
+const KEPT: [&str; 3] = [LEDGER_FILE, SNAPSHOTS_FOLDER, DATABASE_FILE];
 
-pub struct CachedClient {
-    api_client: Box<dyn Client>,
+// Each locale and season has its own folder, so several of them can be cached
+// side by side, as the names of players and teams depend on the locale:
+//
+// .matchday/<locale>/seasons.json
+// .matchday/<locale>/<season>/competitors.json
+// .matchday/<locale>/<season>/standings.json
+// .matchday/<locale>/<season>/schedules.json
+// .matchday/<locale>/<season>/stats/<competitor>.json
+//
+// Files are only read when their data is first requested, and are
+// ignored once they're older than the configured TTL, or than a day for the
+// seasons and standings without one. Offline, every cached file is read
+// however old it is, and nothing is stale.
+//
+// The quota ledger and the snapshots are kept next to them, see `Ledger`
+// and `Snapshots`.
+pub struct CachedClient<C> {
+    client: C,
+    cache_dir: PathBuf,
+    // Folder of the configured locale
     base_path: PathBuf,
-    competitors: Option<SeasonCompetitors>,
+    ttl: Option<Duration>,
+    // Of the seasons and standings, when there's no TTL
+    listings_ttl: Option<Duration>,
+    // Never fetches again what is cached
+    offline: bool,
+    memory: Mutex<Memory>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+// What is cached of a season, in the folder of the configured locale
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedSeason {
+    pub id: String,
+    // Only known once the seasons are cached
+    pub year: Option<String>,
+    // Teams with cached stats
+    pub teams: usize,
+    pub standings: bool,
+    pub schedules: bool,
+    // Of the oldest file, which expires first
+    pub updated_at: Option<DateTime<Utc>>,
+    pub expired: bool,
+}
+
+// What was already read or fetched
+#[derive(Default)]
+struct Memory {
+    seasons: Option<CompetitionSeasons>,
+    competitors: HashMap<String, SeasonCompetitors>,
     stats: HashMap<PathBuf, CompetitorStats>,
+    standings: HashMap<String, SeasonStandings>,
+    schedules: HashMap<String, SeasonSchedules>,
 }
 
-impl CachedClient {
-    pub fn new() -> Result<Self, Error> {
-        let base_path = Self::base_path();
+impl<C: Client> CachedClient<C> {
+    // Caches what the client fetches in the folder, without expiring
+    pub fn new(client: C, cache_dir: impl Into<PathBuf>) -> Self {
+        let cache_dir = cache_dir.into();
         // Ignore the error if it already exists.
         // If it fails because of something else,
         // the other fs calls will error accordingly.
-        let _ = fs::create_dir(&base_path);
+        let _ = fs::create_dir_all(&cache_dir);
 
-        let competitors = Self::read_competitors_file(&base_path)?;
-        let stats = Self::read_stats_dir(&base_path)?;
+        Self {
+            client,
+            base_path: cache_dir.clone(),
+            cache_dir,
+            ttl: None,
+            listings_ttl: None,
+            offline: false,
+            memory: Mutex::new(Memory::default()),
+            metrics: None,
+        }
+    }
 
-        Ok(Self {
-            api_client: Box::new(SportsApiClient::new()?),
-            base_path,
-            competitors,
-            stats,
-        })
+    // In a folder of the configured locale, expiring after the configured TTL
+    pub fn from_config(client: C, config: &Config) -> Self {
+        let mut cached = Self::new(client, &config.cache_dir)
+            .with_ttl(config.cache_ttl)
+            .with_listings_ttl(Some(LISTINGS_TTL));
+        cached.base_path = Self::locale_dir(&config.cache_dir, &config.locale);
+        let _ = fs::create_dir_all(&cached.base_path);
+        cached
     }
 
-    #[cfg(test)]
-    fn set_client(&mut self, client: Box<dyn Client>) {
-        self.api_client = client;
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
     }
 
-    pub async fn get_competitors(&mut self) -> Result<&SeasonCompetitors, Error> {
-        match self.competitors {
-            Some(ref competitors) => Ok(competitors),
-            None => {
-                let competitors = self.api_client.fetch_competitors().await?;
+    // Expires the seasons and standings, unless there's a TTL for every file
+    pub fn with_listings_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.listings_ttl = ttl;
+        self
+    }
+
+    // Reads whatever is cached, even if it expired or the season isn't over,
+    // as the client can't fetch it again
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    // Counts the cache hits, misses and writes
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn count(&self, event: impl Fn(&Metrics)) {
+        if let Some(metrics) = &self.metrics {
+            event(metrics);
+        }
+    }
+
+    fn memory(&self) -> MutexGuard<'_, Memory> {
+        self.memory.lock().unwrap()
+    }
+
+    fn merge_schedules(cached: SeasonSchedules, fetched: SeasonSchedules) -> SeasonSchedules {
+        let mut finished: HashMap<String, MatchSummary> = cached
+            .schedules
+            .into_iter()
+            .filter(|summary| summary.sport_event_status.status.is_finished())
+            .map(|summary| (summary.sport_event.id.clone(), summary))
+            .collect();
+
+        SeasonSchedules {
+            schedules: fetched
+                .schedules
+                .into_iter()
+                .map(|summary| finished.remove(&summary.sport_event.id).unwrap_or(summary))
+                .collect(),
+        }
+    }
+
+    // Only the files of the layout above, in the folder of every locale, as
+    // the cache dir may be shared with anything else. The quota ledger and the
+    // snapshots are kept, they can't be fetched again.
+    pub fn clear(&self) -> io::Result<()> {
+        *self.memory() = Memory::default();
+        for entry in fs::read_dir(&self.cache_dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() || KEPT.iter().any(|kept| entry.file_name() == *kept) {
+                continue;
+            }
+            Self::clear_locale(&entry.path())?;
+        }
+        Ok(())
+    }
 
-                self.write_competitors_to_file(&competitors)?;
+    fn clear_locale(locale_dir: &Path) -> io::Result<()> {
+        let seasons_file = Self::seasons_file(locale_dir);
+        if Self::read_file::<CompetitionSeasons>(&seasons_file)
+            .is_ok_and(|seasons| seasons.is_some())
+        {
+            fs::remove_file(&seasons_file)?;
+        }
+        for entry in fs::read_dir(locale_dir)? {
+            let entry = entry?;
+            let season = entry.file_name().to_string_lossy().into_owned();
+            if !entry.file_type()?.is_dir() || !season.starts_with("sr:season:") {
+                continue;
+            }
+            for file in [
+                Self::competitors_file(locale_dir, &season),
+                Self::standings_file(locale_dir, &season),
+                Self::schedules_file(locale_dir, &season),
+            ] {
+                Self::remove_file(&file)?;
+            }
+            let stats_dir = Self::season_dir(locale_dir, &season).join("stats");
+            if stats_dir.is_dir() {
+                for entry in fs::read_dir(&stats_dir)? {
+                    let file = entry?.path();
+                    if file
+                        .extension()
+                        .is_some_and(|extension| extension == "json")
+                    {
+                        Self::remove_file(&file)?;
+                    }
+                }
+            }
+            Self::remove_empty_dir(&stats_dir);
+            Self::remove_empty_dir(&entry.path());
+        }
+        Self::remove_empty_dir(locale_dir);
+        Ok(())
+    }
+
+    // Calls made by `refresh` at most, for the cached teams of the season, or
+    // as many as a Premier League season has if they weren't cached yet
+    pub fn refresh_calls(&self, season: &str) -> Result<u64, Error> {
+        let teams = self
+            .cached_competitors(season)?
+            .map_or(PREMIER_LEAGUE_TEAMS, |competitors| {
+                competitors.season_competitors.len()
+            });
+        // The seasons, competitors, standings and schedules, and the stats of each team
+        Ok(4 + teams as u64)
+    }
+
+    // Calls made by `refresh_stale` at most, for the files missing or older
+    // than `max_age`
+    pub fn stale_calls(&self, season: &str, max_age: Duration) -> Result<u64, Error> {
+        let max_age = Some(max_age);
+        let mut calls = 0;
+        for file in [
+            Self::seasons_file(&self.base_path),
+            Self::competitors_file(&self.base_path, season),
+            Self::standings_file(&self.base_path, season),
+            Self::schedules_file(&self.base_path, season),
+        ] {
+            if self.is_older(&file, max_age)? {
+                calls += 1;
+            }
+        }
+        match self.cached_competitors(season)? {
+            Some(competitors) => {
+                let teams = competitors
+                    .season_competitors
+                    .iter()
+                    .map(|competitor| competitor.id.as_str());
+                calls += self.stale_teams(season, teams, max_age)?.len() as u64;
+            }
+            None => calls += PREMIER_LEAGUE_TEAMS as u64,
+        }
+        Ok(calls)
+    }
+
+    // Calls made by `refresh_stale_stats` at most
+    pub fn stale_stats_calls(
+        &self,
+        season: &str,
+        teams: &[String],
+        max_age: Duration,
+    ) -> Result<u64, Error> {
+        let teams = teams.iter().map(String::as_str);
+        Ok(self.stale_teams(season, teams, Some(max_age))?.len() as u64)
+    }
+
+    // Teams whose stats are missing or older than `max_age`
+    fn stale_teams<'a>(
+        &self,
+        season: &str,
+        teams: impl IntoIterator<Item = &'a str>,
+        max_age: Option<Duration>,
+    ) -> Result<Vec<&'a str>, Error> {
+        let mut stale = Vec::new();
+        for team in teams {
+            if self.is_older(&Self::stats_file(&self.base_path, season, team), max_age)? {
+                stale.push(team);
+            }
+        }
+        Ok(stale)
+    }
+
+    fn cached_competitors(&self, season: &str) -> Result<Option<SeasonCompetitors>, Error> {
+        if let Some(competitors) = self.memory().competitors.get(season).cloned() {
+            return Ok(Some(competitors));
+        }
+        Self::read_file(&Self::competitors_file(&self.base_path, season))
+    }
+
+    // The cached seasons ordered by ID, only the seasons file is read
+    pub fn status(&self) -> Result<Vec<CachedSeason>, Error> {
+        let years: HashMap<String, String> =
+            Self::read_file::<CompetitionSeasons>(&Self::seasons_file(&self.base_path))?
+                .map(|competition| competition.seasons)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|season| (season.id, season.year))
+                .collect();
+        let entries = |dir: &Path| -> Result<Vec<PathBuf>, Error> {
+            fs::read_dir(dir)
+                .and_then(|entries| entries.map(|entry| Ok(entry?.path())).collect())
+                .map_err(|io_err| IOError::new(dir.to_path_buf(), io_err).into())
+        };
 
-                self.competitors = Some(competitors);
+        let mut seasons = Vec::new();
+        for season_dir in entries(&self.base_path)? {
+            if !season_dir.is_dir() {
+                continue;
+            }
+            let id = season_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let stats_dir = season_dir.join("stats");
+            let stats = if stats_dir.is_dir() {
+                entries(&stats_dir)?
+            } else {
+                vec![]
+            };
+            let standings = Self::standings_file(&self.base_path, &id);
+            let schedules = Self::schedules_file(&self.base_path, &id);
 
-                Ok(self.competitors.as_ref().unwrap())
+            let mut files = vec![
+                Self::competitors_file(&self.base_path, &id),
+                standings.clone(),
+                schedules.clone(),
+            ];
+            files.extend(stats.iter().cloned());
+            let mut oldest: Option<SystemTime> = None;
+            for file in files.iter().filter(|file| file.exists()) {
+                let modified = fs::metadata(file)
+                    .and_then(|metadata| metadata.modified())
+                    .map_err(|io_err| IOError::new(file.clone(), io_err))?;
+                oldest = Some(oldest.map_or(modified, |oldest| oldest.min(modified)));
             }
+            let expired = match (oldest, self.ttl) {
+                (Some(oldest), Some(ttl)) => {
+                    SystemTime::now().duration_since(oldest).unwrap_or_default() > ttl
+                }
+                _ => false,
+            };
+
+            seasons.push(CachedSeason {
+                year: years.get(&id).cloned(),
+                teams: stats.len(),
+                standings: standings.exists(),
+                schedules: schedules.exists(),
+                updated_at: oldest.map(DateTime::<Utc>::from),
+                expired,
+                id,
+            });
         }
+
+        seasons.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(seasons)
+    }
+
+    // Fetches the seasons and the data of a season again, replacing what was
+    // cached even if it didn't expire
+    pub async fn refresh(&self, season: &str) -> Result<(), Error> {
+        self.refresh_older(season, None).await
+    }
+
+    // Fetches again only the files missing or older than `max_age`, so long
+    // running processes stay up to date without fetching what's still recent
+    pub async fn refresh_stale(&self, season: &str, max_age: Duration) -> Result<(), Error> {
+        self.refresh_older(season, Some(max_age)).await
     }
 
-    pub async fn get_competitor_stats(&mut self, id: &str) -> Result<&CompetitorStats, Error> {
-        let stats_file = Self::stats_file(&self.base_path, id);
-        if self.stats.contains_key(&stats_file) {
-            return Ok(self.stats.get(&stats_file).unwrap());
+    #[instrument(level = "debug", skip(self))]
+    async fn refresh_older(&self, season: &str, max_age: Option<Duration>) -> Result<(), Error> {
+        let seasons_file = Self::seasons_file(&self.base_path);
+        if self.is_older(&seasons_file, max_age)? {
+            let seasons = self.client.fetch_seasons().await?;
+            self.store("seasons", &seasons_file, &seasons)?;
+            self.memory().seasons = Some(seasons);
+        }
+
+        let competitors_file = Self::competitors_file(&self.base_path, season);
+        let competitors = match self.cached_competitors(season)? {
+            Some(competitors) if !self.is_older(&competitors_file, max_age)? => competitors,
+            _ => {
+                let competitors = self.client.fetch_competitors(season).await?;
+                self.store("competitors", &competitors_file, &competitors)?;
+                competitors
+            }
+        };
+        let teams = competitors
+            .season_competitors
+            .iter()
+            .map(|competitor| competitor.id.as_str());
+        for team in self.stale_teams(season, teams, max_age)? {
+            self.refresh_stats(season, team).await?;
         }
+        self.memory()
+            .competitors
+            .insert(season.to_string(), competitors);
 
-        let stats = self.api_client.fetch_competitor_stats(id).await?;
+        let standings_file = Self::standings_file(&self.base_path, season);
+        if self.is_older(&standings_file, max_age)? {
+            let standings = self.client.fetch_standings(season).await?;
+            self.store("standings", &standings_file, &standings)?;
+            self.memory()
+                .standings
+                .insert(season.to_string(), standings);
+        }
 
-        self.write_stats_to_file(&stats_file, &stats)?;
+        // Finished matches are still kept as they were first cached
+        if self.is_older(&Self::schedules_file(&self.base_path, season), max_age)? {
+            self.memory().schedules.remove(season);
+            self.fetch_schedules(season).await?;
+        }
 
-        self.stats.insert(stats_file.clone(), stats);
+        Ok(())
+    }
 
-        Ok(self.stats.get(&stats_file).unwrap())
+    // Fetches again only the stats of the given teams missing or older than
+    // `max_age`, such as the teams playing while watching a leaderboard
+    pub async fn refresh_stale_stats(
+        &self,
+        season: &str,
+        teams: &[String],
+        max_age: Duration,
+    ) -> Result<(), Error> {
+        let teams = teams.iter().map(String::as_str);
+        for team in self.stale_teams(season, teams, Some(max_age))? {
+            self.refresh_stats(season, team).await?;
+        }
+        Ok(())
     }
 
-    pub fn clear(&mut self) -> io::Result<()> {
-        self.competitors = None;
-        self.stats.clear();
-        fs::remove_dir_all(&self.base_path)?;
+    async fn refresh_stats(&self, season: &str, id: &str) -> Result<(), Error> {
+        let stats_file = Self::stats_file(&self.base_path, season, id);
+        let stats = self.client.fetch_competitor_stats(season, id).await?;
+        self.store("competitor_stats", &stats_file, &stats)?;
+        self.memory().stats.insert(stats_file, stats);
         Ok(())
     }
 
     // path methods
-    fn base_path() -> PathBuf {
-        // Ideally could use a library to run consistently on Windows
-        #[allow(deprecated)]
-        let mut base_path = std::env::home_dir().expect("should have home dir");
-        base_path.push(CACHE_FOLDER);
-        base_path
+    fn locale_dir(cache_dir: &Path, locale: &str) -> PathBuf {
+        let mut locale_dir = cache_dir.to_path_buf();
+        locale_dir.push(locale);
+        locale_dir
     }
-    fn competitors_file(base_path: &Path) -> PathBuf {
-        let mut competitors_file = base_path.to_path_buf();
+    fn seasons_file(base_path: &Path) -> PathBuf {
+        let mut seasons_file = base_path.to_path_buf();
+        seasons_file.push("seasons.json");
+        seasons_file
+    }
+    fn season_dir(base_path: &Path, season: &str) -> PathBuf {
+        let mut season_dir = base_path.to_path_buf();
+        season_dir.push(season);
+        season_dir
+    }
+    fn competitors_file(base_path: &Path, season: &str) -> PathBuf {
+        let mut competitors_file = Self::season_dir(base_path, season);
         competitors_file.push("competitors.json");
         competitors_file
     }
-    fn stats_dir(base_path: &Path) -> PathBuf {
-        let mut stats_dir = base_path.to_path_buf();
-        stats_dir.push("stats");
-        stats_dir
+    fn standings_file(base_path: &Path, season: &str) -> PathBuf {
+        let mut standings_file = Self::season_dir(base_path, season);
+        standings_file.push("standings.json");
+        standings_file
     }
-    fn stats_file(base_path: &Path, id: &str) -> PathBuf {
-        let mut stats_file = Self::stats_dir(base_path);
+    fn schedules_file(base_path: &Path, season: &str) -> PathBuf {
+        let mut schedules_file = Self::season_dir(base_path, season);
+        schedules_file.push("schedules.json");
+        schedules_file
+    }
+    fn stats_file(base_path: &Path, season: &str, id: &str) -> PathBuf {
+        let mut stats_file = Self::season_dir(base_path, season);
+        stats_file.push("stats");
         stats_file.push(id);
         stats_file.set_extension("json");
         stats_file
     }
 
     // fs methods
-    fn read_competitors_file(base_path: &Path) -> Result<Option<SeasonCompetitors>, Error> {
-        let competitors_file = Self::competitors_file(base_path);
-        if competitors_file.exists() {
-            let raw_competitors = fs::read_to_string(&competitors_file)
-                .map_err(|io_err| IOError::new(competitors_file.clone(), io_err))?;
-            Ok(Some(
-                serde_json::from_str(&raw_competitors)
-                    .map_err(|e| JSONError::new(Some(competitors_file), e))?,
-            ))
-        } else {
-            Ok(None)
+    // Missing files are older than any age, and every file without one.
+    // Offline, only the missing ones are.
+    fn is_older(&self, file: &Path, max_age: Option<Duration>) -> Result<bool, Error> {
+        let Some(max_age) = max_age else {
+            return Ok(true);
+        };
+        if !file.exists() {
+            return Ok(true);
         }
+        Ok(!self.offline && Self::age(file)? > max_age)
+    }
+    fn age(file: &Path) -> Result<Duration, Error> {
+        let modified = fs::metadata(file)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+        Ok(SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default())
     }
-    fn read_stats_dir(base_path: &Path) -> Result<HashMap<PathBuf, CompetitorStats>, Error> {
-        let mut stats = HashMap::new();
-        let stats_dir = Self::stats_dir(base_path);
-        if stats_dir.exists() {
-            for entry in fs::read_dir(&stats_dir)
-                .map_err(|io_err| IOError::new(stats_dir.clone(), io_err))?
-            {
-                let file = entry.map_err(|io_err| IOError::new(stats_dir.clone(), io_err))?;
-                let raw_stat = fs::read_to_string(file.path())
-                    .map_err(|io_err| IOError::new(file.path(), io_err))?;
-                let stat = serde_json::from_str(&raw_stat)
-                    .map_err(|e| JSONError::new(Some(file.path()), e))?;
-                stats.insert(file.path(), stat);
+    fn is_fresh(file: &Path, ttl: Option<Duration>) -> Result<bool, Error> {
+        let Some(ttl) = ttl else {
+            return Ok(true);
+        };
+        Ok(Self::age(file)? <= ttl)
+    }
+    fn read_fresh_file<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        file: &Path,
+        ttl: Option<Duration>,
+    ) -> Result<Option<T>, Error> {
+        self.read_fresh_file_with(endpoint, file, ttl, Self::read_file)
+    }
+    fn read_fresh_file_with<T>(
+        &self,
+        endpoint: &str,
+        file: &Path,
+        ttl: Option<Duration>,
+        read: impl FnOnce(&Path) -> Result<Option<T>, Error>,
+    ) -> Result<Option<T>, Error> {
+        if file.exists() && !self.offline && !Self::is_fresh(file, ttl)? {
+            debug!(file = %file.display(), "cache expired");
+            self.count(|metrics| metrics.cache_miss(endpoint));
+            return Ok(None);
+        }
+        let value = read(file)?;
+        match value {
+            Some(_) => {
+                debug!(file = %file.display(), "cache hit");
+                self.count(|metrics| metrics.cache_hit(endpoint));
+            }
+            None => {
+                debug!(file = %file.display(), "cache miss");
+                self.count(|metrics| metrics.cache_miss(endpoint));
             }
+        }
+        Ok(value)
+    }
+    fn read_file<T: DeserializeOwned>(file: &Path) -> Result<Option<T>, Error> {
+        if file.exists() {
+            let raw = fs::read_to_string(file)
+                .map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+            Ok(Some(serde_json::from_str(&raw).map_err(|e| {
+                JSONError::new(Some(file.to_path_buf()), e)
+            })?))
         } else {
-            // Ignore the error if it already exists.
-            // If it fails because of something else,
-            // the other fs calls will error accordingly.
-            let _ = fs::create_dir(&stats_dir);
+            Ok(None)
+        }
+    }
+    // Stats cached before the team data was stored don't have it, they're
+    // fetched again rather than read as zeros
+    fn read_stats_file(file: &Path) -> Result<Option<CompetitorStats>, Error> {
+        let Some(value) = Self::read_file::<serde_json::Value>(file)? else {
+            return Ok(None);
         };
-        Ok(stats)
+        if value["competitor"].get("statistics").is_none() {
+            debug!(file = %file.display(), "cached without the team stats");
+            return Ok(None);
+        }
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| JSONError::new(Some(file.to_path_buf()), e).into())
     }
-    fn write_competitors_to_file(&self, competitors: &SeasonCompetitors) -> Result<(), Error> {
-        let competitors_file = Self::competitors_file(&self.base_path);
-        let _ = fs::File::create(&competitors_file);
-        fs::write(
-            &competitors_file,
-            serde_json::to_string(&competitors).map_err(|e| JSONError::new(None, e))?,
-        )
-        .map_err(|io_err| IOError::new(competitors_file, io_err))?;
+    fn store<T: Serialize>(&self, endpoint: &str, file: &Path, value: &T) -> Result<(), Error> {
+        Self::write_file(file, value)?;
+        self.count(|metrics| metrics.cache_write(endpoint));
         Ok(())
     }
-    fn write_stats_to_file(
-        &self,
-        stats_file: &PathBuf,
-        stats: &CompetitorStats,
-    ) -> Result<(), Error> {
-        let _ = fs::File::create(stats_file);
+    fn remove_file(file: &Path) -> io::Result<()> {
+        match fs::remove_file(file) {
+            Err(io_err) if io_err.kind() != io::ErrorKind::NotFound => Err(io_err),
+            _ => Ok(()),
+        }
+    }
+    // Kept if anything else is in it
+    fn remove_empty_dir(dir: &Path) {
+        let _ = fs::remove_dir(dir);
+    }
+    fn write_file<T: Serialize>(file: &Path, value: &T) -> Result<(), Error> {
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir).map_err(|io_err| IOError::new(dir.to_path_buf(), io_err))?;
+        }
         fs::write(
-            stats_file,
-            serde_json::to_string(stats).map_err(|e| JSONError::new(None, e))?,
+            file,
+            serde_json::to_string(value).map_err(|e| JSONError::new(None, e))?,
         )
-        .map_err(|io_err| IOError::new(stats_file.clone(), io_err))?;
+        .map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
         Ok(())
     }
 }
 
+#[async_trait]
+impl<C: Client> Client for CachedClient<C> {
+    #[instrument(level = "debug", skip(self))]
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        if let Some(seasons) = self.memory().seasons.clone() {
+            trace!("memory hit");
+            self.count(|metrics| metrics.cache_hit("seasons"));
+            return Ok(seasons);
+        }
+
+        let seasons_file = Self::seasons_file(&self.base_path);
+        let seasons =
+            match self.read_fresh_file("seasons", &seasons_file, self.ttl.or(self.listings_ttl))? {
+                Some(seasons) => seasons,
+                None => {
+                    let seasons = self.client.fetch_seasons().await?;
+                    self.store("seasons", &seasons_file, &seasons)?;
+                    seasons
+                }
+            };
+        self.memory().seasons = Some(seasons.clone());
+
+        Ok(seasons)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        if let Some(competitors) = self.memory().competitors.get(season).cloned() {
+            trace!("memory hit");
+            self.count(|metrics| metrics.cache_hit("competitors"));
+            return Ok(competitors);
+        }
+
+        let competitors_file = Self::competitors_file(&self.base_path, season);
+        let competitors = match self.read_fresh_file("competitors", &competitors_file, self.ttl)? {
+            Some(competitors) => competitors,
+            None => {
+                let competitors = self.client.fetch_competitors(season).await?;
+                self.store("competitors", &competitors_file, &competitors)?;
+                competitors
+            }
+        };
+        self.memory()
+            .competitors
+            .insert(season.to_string(), competitors.clone());
+
+        Ok(competitors)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        let stats_file = Self::stats_file(&self.base_path, season, id);
+        if let Some(stats) = self.memory().stats.get(&stats_file).cloned() {
+            trace!("memory hit");
+            self.count(|metrics| metrics.cache_hit("competitor_stats"));
+            return Ok(stats);
+        }
+
+        let stats = match self.read_fresh_file_with(
+            "competitor_stats",
+            &stats_file,
+            self.ttl,
+            Self::read_stats_file,
+        )? {
+            Some(stats) => stats,
+            None => {
+                let stats = self.client.fetch_competitor_stats(season, id).await?;
+                self.store("competitor_stats", &stats_file, &stats)?;
+                stats
+            }
+        };
+        self.memory().stats.insert(stats_file, stats.clone());
+
+        Ok(stats)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        if let Some(standings) = self.memory().standings.get(season).cloned() {
+            trace!("memory hit");
+            self.count(|metrics| metrics.cache_hit("standings"));
+            return Ok(standings);
+        }
+
+        let standings_file = Self::standings_file(&self.base_path, season);
+        let standings = match self.read_fresh_file(
+            "standings",
+            &standings_file,
+            self.ttl.or(self.listings_ttl),
+        )? {
+            Some(standings) => standings,
+            None => {
+                let standings = self.client.fetch_standings(season).await?;
+                self.store("standings", &standings_file, &standings)?;
+                standings
+            }
+        };
+        self.memory()
+            .standings
+            .insert(season.to_string(), standings.clone());
+
+        Ok(standings)
+    }
+
+    // Unlike the other data, schedules change while the season is played.
+    // They're fetched again until every match is finished, but the
+    // finished ones are kept as they were first cached.
+    #[instrument(level = "debug", skip(self))]
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        let schedules_file = Self::schedules_file(&self.base_path, season);
+        let remembered = self.memory().schedules.get(season).cloned();
+        let cached = match remembered {
+            Some(cached) => Some(cached),
+            None => Self::read_file(&schedules_file)?,
+        };
+
+        let all_finished = cached.as_ref().is_some_and(|cached| {
+            cached
+                .schedules
+                .iter()
+                .all(|summary| summary.sport_event_status.status.is_finished())
+        });
+        // Without a TTL they would never be fetched again, unless offline
+        let fresh = cached.is_some()
+            && (self.offline || self.ttl.is_some() && Self::is_fresh(&schedules_file, self.ttl)?);
+        let schedules = match cached {
+            Some(cached) if all_finished || fresh => {
+                self.count(|metrics| metrics.cache_hit("schedules"));
+                cached
+            }
+            cached => {
+                self.count(|metrics| metrics.cache_miss("schedules"));
+                debug!(cached = cached.is_some(), "fetching unfinished schedules");
+                let fetched = self.client.fetch_schedules(season).await?;
+                let schedules = match cached {
+                    Some(cached) => Self::merge_schedules(cached, fetched),
+                    None => fetched,
+                };
+                self.store("schedules", &schedules_file, &schedules)?;
+                schedules
+            }
+        };
+        self.memory()
+            .schedules
+            .insert(season.to_string(), schedules.clone());
+
+        Ok(schedules)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use async_trait::async_trait;
     use std::fs;
-    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
 
-    use crate::cached_client::{CachedClient, CACHE_FOLDER};
+    use crate::cached_client::CachedClient;
     use crate::client::Client;
+    use crate::config::Config;
     use crate::error::Error;
     use crate::types::{
-        CompetitorPlayers, CompetitorStats, Player, PlayerStats, SeasonCompetitor,
-        SeasonCompetitors,
+        CompetitionSeasons, Competitor, CompetitorRef, CompetitorStats, MatchStatus, MatchSummary,
+        Player, PlayerStats, Season, SeasonCompetitor, SeasonCompetitors, SeasonSchedules,
+        SeasonStandings, SportEvent, SportEventContext, SportEventStatus, Standing, StandingGroup,
+        StandingRow, StandingType,
     };
 
+    const SEASON_ID: &str = "sr:season:105353";
+
     #[derive(Clone)]
     struct FakeClient {
+        seasons: CompetitionSeasons,
         competitors: SeasonCompetitors,
         stats: CompetitorStats,
+        standings: SeasonStandings,
+        schedules: SeasonSchedules,
     }
 
     impl FakeClient {
         fn new() -> Self {
             Self {
+                seasons: CompetitionSeasons {
+                    seasons: vec![Season {
+                        id: "sr:season:105353".to_string(),
+                        name: "Premier League 23/24".to_string(),
+                        start_date: "2023-08-11".parse().unwrap(),
+                        end_date: "2024-05-19".parse().unwrap(),
+                        year: "23/24".to_string(),
+                    }],
+                },
                 competitors: SeasonCompetitors {
                     season_competitors: vec![
                         SeasonCompetitor {
@@ -201,7 +806,11 @@ mod test {
                     ],
                 },
                 stats: CompetitorStats {
-                    competitor: CompetitorPlayers {
+                    competitor: Competitor {
+                        id: "sr:competitor:17".to_string(),
+                        name: "Manchester City".to_string(),
+                        abbreviation: "MCI".to_string(),
+                        statistics: Default::default(),
                         players: vec![
                             Player {
                                 id: "sr:player:1234".to_string(),
@@ -209,6 +818,7 @@ mod test {
                                 statistics: PlayerStats {
                                     assists: 100,
                                     goals_scored: 100,
+                                    ..Default::default()
                                 },
                             },
                             Player {
@@ -217,41 +827,462 @@ mod test {
                                 statistics: PlayerStats {
                                     assists: 50,
                                     goals_scored: 50,
+                                    ..Default::default()
                                 },
                             },
                         ],
                     },
                 },
+                standings: SeasonStandings {
+                    standings: vec![Standing {
+                        kind: StandingType::Total,
+                        groups: vec![StandingGroup {
+                            standings: vec![StandingRow {
+                                rank: 1,
+                                competitor: CompetitorRef {
+                                    id: "sr:competitor:17".to_string(),
+                                    name: "Manchester City".to_string(),
+                                    abbreviation: "MCI".to_string(),
+                                },
+                                played: 38,
+                                win: 28,
+                                draw: 7,
+                                loss: 3,
+                                goals_for: 96,
+                                goals_against: 34,
+                                goals_diff: 62,
+                                points: 91,
+                            }],
+                        }],
+                    }],
+                },
+                schedules: SeasonSchedules {
+                    schedules: vec![
+                        mock_match("sr:sport_event:1", MatchStatus::Closed, Some((0, 3))),
+                        mock_match("sr:sport_event:2", MatchStatus::NotStarted, None),
+                    ],
+                },
             }
         }
     }
 
+    fn mock_match(id: &str, status: MatchStatus, score: Option<(usize, usize)>) -> MatchSummary {
+        MatchSummary {
+            sport_event: SportEvent {
+                id: id.to_string(),
+                start_time: "2023-08-11T19:00:00Z".parse().unwrap(),
+                sport_event_context: SportEventContext::default(),
+                competitors: vec![],
+            },
+            sport_event_status: SportEventStatus {
+                status,
+                home_score: score.map(|(home, _)| home),
+                away_score: score.map(|(_, away)| away),
+            },
+        }
+    }
+
     #[async_trait]
     impl Client for FakeClient {
-        async fn fetch_competitors(&self) -> Result<SeasonCompetitors, Error> {
+        async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+            Ok(self.seasons.clone())
+        }
+        async fn fetch_competitors(&self, _season: &str) -> Result<SeasonCompetitors, Error> {
             Ok(self.competitors.clone())
         }
-        async fn fetch_competitor_stats(&self, _id: &str) -> Result<CompetitorStats, Error> {
+        async fn fetch_competitor_stats(
+            &self,
+            _season: &str,
+            _id: &str,
+        ) -> Result<CompetitorStats, Error> {
             Ok(self.stats.clone())
         }
+        async fn fetch_standings(&self, _season: &str) -> Result<SeasonStandings, Error> {
+            Ok(self.standings.clone())
+        }
+        async fn fetch_schedules(&self, _season: &str) -> Result<SeasonSchedules, Error> {
+            Ok(self.schedules.clone())
+        }
+    }
+
+    fn mock_config(cache_folder: &str) -> Config {
+        #[allow(deprecated)]
+        let mut cache_dir = std::env::home_dir().unwrap();
+        cache_dir.push(cache_folder);
+        Config {
+            api_key: Some("asdf1234".into()),
+            cache_dir,
+            ..Default::default()
+        }
     }
 
     #[tokio::test]
     async fn test_fetching() {
-        #[allow(deprecated)]
-        let mut cache_dir = PathBuf::from(std::env::home_dir().unwrap());
-        cache_dir.push(CACHE_FOLDER);
+        let config = mock_config(".tmp-cache-matchday");
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let fake_client = FakeClient::new();
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+
+        let seasons = cached.fetch_seasons().await.unwrap();
+        assert_eq!(seasons, fake_client.seasons);
+
+        let competitors = cached.fetch_competitors(SEASON_ID).await.unwrap();
+        assert_eq!(competitors, fake_client.competitors);
+
+        let stats = cached
+            .fetch_competitor_stats(SEASON_ID, "not used")
+            .await
+            .unwrap();
+        assert_eq!(stats, fake_client.stats);
+
+        let standings = cached.fetch_standings(SEASON_ID).await.unwrap();
+        assert_eq!(standings, fake_client.standings);
+
+        let schedules = cached.fetch_schedules(SEASON_ID).await.unwrap();
+        assert_eq!(schedules, fake_client.schedules);
+
+        let mut competitors_file = cache_dir.clone();
+        competitors_file.extend(["en", SEASON_ID, "competitors.json"]);
+        assert!(competitors_file.exists());
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn test_merge_schedules_keeps_finished_matches() {
+        let cached = SeasonSchedules {
+            schedules: vec![
+                mock_match("sr:sport_event:1", MatchStatus::Closed, Some((0, 3))),
+                mock_match("sr:sport_event:2", MatchStatus::NotStarted, None),
+            ],
+        };
+        let fetched = SeasonSchedules {
+            schedules: vec![
+                // finished matches must not be overwritten, even by corrections
+                mock_match("sr:sport_event:1", MatchStatus::Closed, Some((1, 3))),
+                mock_match("sr:sport_event:2", MatchStatus::Live, Some((1, 0))),
+                mock_match("sr:sport_event:3", MatchStatus::NotStarted, None),
+            ],
+        };
+
+        assert_eq!(
+            CachedClient::<FakeClient>::merge_schedules(cached, fetched),
+            SeasonSchedules {
+                schedules: vec![
+                    mock_match("sr:sport_event:1", MatchStatus::Closed, Some((0, 3))),
+                    mock_match("sr:sport_event:2", MatchStatus::Live, Some((1, 0))),
+                    mock_match("sr:sport_event:3", MatchStatus::NotStarted, None),
+                ],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ttl() {
+        let mut config = mock_config(".tmp-cache-matchday-ttl");
+        config.cache_ttl = Some(Duration::from_secs(60 * 60));
+        let cache_dir = config.cache_dir.clone();
         let _ = fs::remove_dir_all(&cache_dir);
 
-        let mut cached = CachedClient::new().unwrap();
         let fake_client = FakeClient::new();
-        cached.set_client(Box::new(fake_client.clone()));
+        let outdated = SeasonCompetitors {
+            season_competitors: vec![],
+        };
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        let competitors_file = CachedClient::<FakeClient>::competitors_file(&locale_dir, SEASON_ID);
+        CachedClient::<FakeClient>::write_file(&competitors_file, &outdated).unwrap();
+
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(cached.fetch_competitors(SEASON_ID).await.unwrap(), outdated);
+
+        // Once older than the TTL, the file is fetched again
+        let two_hours_ago = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&competitors_file)
+            .unwrap()
+            .set_modified(two_hours_ago)
+            .unwrap();
+
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(
+            cached.fetch_competitors(SEASON_ID).await.unwrap(),
+            fake_client.competitors
+        );
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_stats_without_team_data() {
+        let config = mock_config(".tmp-cache-matchday-old-stats");
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        // as cached before the team data was stored
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        let stats_file = CachedClient::<FakeClient>::stats_file(&locale_dir, SEASON_ID, "id");
+        fs::create_dir_all(stats_file.parent().unwrap()).unwrap();
+        fs::write(&stats_file, r#"{"competitor":{"players":[]}}"#).unwrap();
+
+        let fake_client = FakeClient::new();
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(
+            cached
+                .fetch_competitor_stats(SEASON_ID, "id")
+                .await
+                .unwrap(),
+            fake_client.stats
+        );
+        assert_eq!(
+            CachedClient::<FakeClient>::read_stats_file(&stats_file).unwrap(),
+            Some(fake_client.stats)
+        );
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_listings_ttl() {
+        let config = mock_config(".tmp-cache-matchday-listings-ttl");
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let fake_client = FakeClient::new();
+        let outdated = CompetitionSeasons { seasons: vec![] };
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        let seasons_file = CachedClient::<FakeClient>::seasons_file(&locale_dir);
+        CachedClient::<FakeClient>::write_file(&seasons_file, &outdated).unwrap();
+
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(cached.fetch_seasons().await.unwrap(), outdated);
+
+        // Without a TTL, the seasons still expire after a day
+        fs::File::options()
+            .write(true)
+            .open(&seasons_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+            .unwrap();
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(cached.fetch_seasons().await.unwrap(), fake_client.seasons);
+
+        // but not without the config
+        CachedClient::<FakeClient>::write_file(&seasons_file, &outdated).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&seasons_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+            .unwrap();
+        let cached = CachedClient::new(fake_client.clone(), &locale_dir);
+        assert_eq!(cached.fetch_seasons().await.unwrap(), outdated);
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let mut config = mock_config(".tmp-cache-matchday-status");
+        config.cache_ttl = Some(Duration::from_secs(60 * 60));
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let cached = CachedClient::from_config(FakeClient::new(), &config);
+        assert_eq!(cached.status().unwrap(), []);
+
+        cached.fetch_seasons().await.unwrap();
+        cached.fetch_competitors(SEASON_ID).await.unwrap();
+        cached
+            .fetch_competitor_stats(SEASON_ID, "sr:competitor:17")
+            .await
+            .unwrap();
+        cached.fetch_standings(SEASON_ID).await.unwrap();
+        let status = cached.status().unwrap();
+        assert_eq!(status.len(), 1);
+        assert_eq!(
+            (
+                status[0].id.as_str(),
+                status[0].year.as_deref(),
+                status[0].teams,
+                status[0].standings,
+                status[0].schedules,
+                status[0].expired
+            ),
+            (SEASON_ID, Some("23/24"), 1, true, false, false)
+        );
+
+        // The oldest file expires the season
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        fs::File::options()
+            .write(true)
+            .open(CachedClient::<FakeClient>::standings_file(
+                &locale_dir,
+                SEASON_ID,
+            ))
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(2 * 60 * 60))
+            .unwrap();
+        assert!(cached.status().unwrap()[0].expired);
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_clear() {
+        let config = mock_config(".tmp-cache-matchday-clear");
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let fake_client = FakeClient::new();
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        cached.refresh(SEASON_ID).await.unwrap();
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        let season_dir = CachedClient::<FakeClient>::season_dir(&locale_dir, SEASON_ID);
+        // Anything else in the cache dir, such as a project the cache was
+        // pointed at, is kept
+        let others = [
+            cache_dir.join("notes.txt"),
+            cache_dir.join("src").join("seasons.json"),
+            cache_dir.join("src").join("main").join("standings.json"),
+            season_dir.join("notes.txt"),
+        ];
+        for file in &others {
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, "{}").unwrap();
+        }
+
+        cached.clear().unwrap();
+        assert!(!CachedClient::<FakeClient>::seasons_file(&locale_dir).exists());
+        assert!(!CachedClient::<FakeClient>::competitors_file(&locale_dir, SEASON_ID).exists());
+        assert!(!season_dir.join("stats").exists());
+        for file in &others {
+            assert!(file.exists(), "{}", file.display());
+        }
+        assert_eq!(
+            cached.fetch_competitors(SEASON_ID).await.unwrap(),
+            fake_client.competitors
+        );
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_refresh() {
+        let config = mock_config(".tmp-cache-matchday-refresh");
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let fake_client = FakeClient::new();
+        // as many teams as the Premier League before they're cached
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(cached.refresh_calls(SEASON_ID).unwrap(), 24);
+
+        let outdated = SeasonCompetitors {
+            season_competitors: vec![],
+        };
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        let competitors_file = CachedClient::<FakeClient>::competitors_file(&locale_dir, SEASON_ID);
+        CachedClient::<FakeClient>::write_file(&competitors_file, &outdated).unwrap();
+
+        // Without a TTL it never expires, unless refreshed
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(cached.fetch_competitors(SEASON_ID).await.unwrap(), outdated);
+        assert_eq!(cached.refresh_calls(SEASON_ID).unwrap(), 4);
+
+        cached.refresh(SEASON_ID).await.unwrap();
+        assert_eq!(cached.refresh_calls(SEASON_ID).unwrap(), 6);
+        assert_eq!(
+            cached.fetch_competitors(SEASON_ID).await.unwrap(),
+            fake_client.competitors
+        );
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(
+            cached.fetch_competitors(SEASON_ID).await.unwrap(),
+            fake_client.competitors
+        );
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_stale() {
+        let config = mock_config(".tmp-cache-matchday-refresh-stale");
+        let cache_dir = config.cache_dir.clone();
+        let _ = fs::remove_dir_all(&cache_dir);
+        let hour = Duration::from_secs(60 * 60);
+
+        let fake_client = FakeClient::new();
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        assert_eq!(cached.stale_calls(SEASON_ID, hour).unwrap(), 24);
+        cached.refresh_stale(SEASON_ID, hour).await.unwrap();
+        assert_eq!(cached.stale_calls(SEASON_ID, hour).unwrap(), 0);
+
+        // Only the stats older than the max age are fetched again
+        let outdated = CompetitorStats {
+            competitor: Competitor {
+                players: vec![],
+                ..fake_client.stats.competitor.clone()
+            },
+        };
+        let locale_dir = CachedClient::<FakeClient>::locale_dir(&cache_dir, &config.locale);
+        let stats_file =
+            CachedClient::<FakeClient>::stats_file(&locale_dir, SEASON_ID, "sr:competitor:13");
+        CachedClient::<FakeClient>::write_file(&stats_file, &outdated).unwrap();
+        let cached = CachedClient::from_config(fake_client.clone(), &config);
+        cached.refresh_stale(SEASON_ID, hour).await.unwrap();
+        assert_eq!(
+            cached
+                .fetch_competitor_stats(SEASON_ID, "sr:competitor:13")
+                .await
+                .unwrap(),
+            outdated
+        );
 
-        let competitors = cached.get_competitors().await.unwrap();
-        assert_eq!(competitors, &fake_client.competitors);
+        fs::File::options()
+            .write(true)
+            .open(&stats_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * hour)
+            .unwrap();
+        assert_eq!(cached.stale_calls(SEASON_ID, hour).unwrap(), 1);
+        cached.refresh_stale(SEASON_ID, hour).await.unwrap();
+        assert_eq!(
+            cached
+                .fetch_competitor_stats(SEASON_ID, "sr:competitor:13")
+                .await
+                .unwrap(),
+            fake_client.stats
+        );
 
-        let stats = cached.get_competitor_stats("not used").await.unwrap();
-        assert_eq!(stats, &fake_client.stats);
+        // Or only for the given teams
+        let teams = ["sr:competitor:13".to_string()];
+        fs::File::options()
+            .write(true)
+            .open(&stats_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * hour)
+            .unwrap();
+        assert_eq!(
+            cached.stale_stats_calls(SEASON_ID, &teams, hour).unwrap(),
+            1
+        );
+        assert_eq!(
+            cached
+                .stale_stats_calls(SEASON_ID, &teams, 3 * hour)
+                .unwrap(),
+            0
+        );
+        cached
+            .refresh_stale_stats(SEASON_ID, &teams, hour)
+            .await
+            .unwrap();
+        assert_eq!(
+            cached.stale_stats_calls(SEASON_ID, &teams, hour).unwrap(),
+            0
+        );
 
         let _ = fs::remove_dir_all(&cache_dir);
     }
@@ -0,0 +1,235 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, IOError, JSONError};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "endpoint", rename_all = "snake_case")]
+pub enum Request {
+    Seasons,
+    Competitors { season: String },
+    CompetitorStats { season: String, competitor: String },
+    Standings { season: String },
+    Schedules { season: String },
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Request::Seasons => write!(f, "seasons"),
+            Request::Competitors { season } => write!(f, "competitors of {season}"),
+            Request::CompetitorStats { season, competitor } => {
+                write!(f, "stats of {competitor} in {season}")
+            }
+            Request::Standings { season } => write!(f, "standings of {season}"),
+            Request::Schedules { season } => write!(f, "schedules of {season}"),
+        }
+    }
+}
+
+// What the API answered, before it's parsed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawResponse {
+    pub status: u16,
+    // As it was sent, so what the types don't parse is kept too
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Interaction {
+    request: Request,
+    #[serde(flatten)]
+    response: RawResponse,
+}
+
+// Records the responses of the API client into a JSON file, to serve them back
+// later without any network. They're saved as the API sent them, errors
+// included, and parsed again when replayed, so a response the types fail to
+// parse fails the same way. Only the request parameters are saved with them,
+// never the API key or headers. A request recorded again replaces the
+// previous response, such as a rate limited one that was retried.
+pub struct Cassette {
+    file: PathBuf,
+    replaying: bool,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl Cassette {
+    // Keeps what the file already has, so several runs can add to it
+    pub fn record(file: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            file: file.to_path_buf(),
+            replaying: false,
+            interactions: Mutex::new(Self::read_file(file)?.unwrap_or_default()),
+        })
+    }
+
+    pub fn replay(file: &Path) -> Result<Self, Error> {
+        let interactions = Self::read_file(file)?
+            .ok_or_else(|| IOError::new(file.to_path_buf(), std::io::ErrorKind::NotFound.into()))?;
+        Ok(Self {
+            file: file.to_path_buf(),
+            replaying: true,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    // Answers every request, so nothing is sent
+    pub fn replaying(&self) -> bool {
+        self.replaying
+    }
+
+    pub fn replay_response(&self, request: &Request) -> Result<RawResponse, Error> {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|interaction| &interaction.request == request)
+            .map(|interaction| interaction.response.clone())
+            .ok_or_else(|| Error::NotRecorded(request.to_string()))
+    }
+
+    pub fn record_response(&self, request: Request, response: RawResponse) -> Result<(), Error> {
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.retain(|recorded| recorded.request != request);
+        interactions.push(Interaction { request, response });
+        // Written every time, so an interrupted run still keeps its responses
+        Self::write_file(&self.file, &interactions)
+    }
+
+    fn read_file(file: &Path) -> Result<Option<Vec<Interaction>>, Error> {
+        if !file.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read(file).map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+        let interactions = serde_json::from_slice(&raw)
+            .map_err(|json_err| JSONError::new(Some(file.to_path_buf()), json_err))?;
+        Ok(Some(interactions))
+    }
+
+    fn write_file(file: &Path, interactions: &[Interaction]) -> Result<(), Error> {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|io_err| IOError::new(parent.to_path_buf(), io_err))?;
+        }
+        let json = serde_json::to_vec_pretty(interactions)
+            .map_err(|json_err| JSONError::new(Some(file.to_path_buf()), json_err))?;
+        fs::write(file, json).map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::api_client::SportsApiClient;
+    use crate::cassette::Cassette;
+    use crate::client::Client;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let file = std::env::temp_dir().join("matchday-cassette.json");
+        let _ = fs::remove_file(&file);
+        let mut server = mockito::Server::new_async().await;
+        // with a field the types leave out, and a competitor without its ID
+        let seasons = r#"{"generated_at":"2024-07-01T11:02:31+00:00","seasons":[]}"#;
+        let competitors = r#"{"season_competitors":[{"name":"Arsenal FC"}]}"#;
+        let _mocks = [
+            server
+                .mock(
+                    "GET",
+                    "/soccer/trial/v4/en/competitions/sr:competition:17/seasons.json",
+                )
+                .with_body(seasons)
+                .create_async()
+                .await,
+            server
+                .mock(
+                    "GET",
+                    "/soccer/trial/v4/en/seasons/sr:season:1/competitors.json",
+                )
+                .with_body(competitors)
+                .create_async()
+                .await,
+            server
+                .mock(
+                    "GET",
+                    "/soccer/trial/v4/en/seasons/sr:season:1/standings.json",
+                )
+                .with_status(404)
+                .with_body(r#"{"message":"No standings"}"#)
+                .create_async()
+                .await,
+        ];
+
+        let recorder = SportsApiClient::builder()
+            .api_key("asdf1234")
+            .base_url(server.url().parse().unwrap())
+            .cassette(Cassette::record(&file).unwrap())
+            .build()
+            .unwrap();
+        let recorded_seasons = recorder.fetch_seasons().await.unwrap();
+        let competitors_error = recorder
+            .fetch_competitors("sr:season:1")
+            .await
+            .unwrap_err()
+            .to_string();
+        // recorded again, replacing the previous response
+        recorder.fetch_seasons().await.unwrap();
+        let standings_error = recorder
+            .fetch_standings("sr:season:1")
+            .await
+            .unwrap_err()
+            .to_string();
+
+        // saved as they were sent, without the API key
+        let recorded = fs::read_to_string(&file).unwrap();
+        assert!(!recorded.contains("asdf1234"));
+        let recorded: serde_json::Value = serde_json::from_str(&recorded).unwrap();
+        assert_eq!(recorded.as_array().unwrap().len(), 3);
+        assert_eq!(
+            recorded[0],
+            serde_json::json!({
+                "request": { "endpoint": "competitors", "season": "sr:season:1" },
+                "status": 200,
+                "body": competitors,
+            })
+        );
+        assert_eq!(recorded[1]["body"], seasons);
+        assert_eq!(recorded[2]["status"], 404);
+
+        // parsed again, without the API key or the server
+        let player = SportsApiClient::builder()
+            .base_url(server.url().parse().unwrap())
+            .cassette(Cassette::replay(&file).unwrap())
+            .build()
+            .unwrap();
+        drop(server);
+        assert_eq!(player.fetch_seasons().await.unwrap(), recorded_seasons);
+        let replayed = player.fetch_competitors("sr:season:1").await.unwrap_err();
+        assert_eq!(replayed.to_string(), competitors_error);
+        match player.fetch_standings("sr:season:1").await.unwrap_err() {
+            Error::Request(e) => {
+                assert_eq!(e.status().map(|status| status.as_u16()), Some(404));
+                assert_eq!(e.to_string(), standings_error);
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+        assert_eq!(
+            player
+                .fetch_competitors("sr:season:2")
+                .await
+                .unwrap_err()
+                .to_string(),
+            "no recorded response for the competitors of sr:season:2"
+        );
+
+        let _ = fs::remove_file(&file);
+    }
+}
@@ -1,10 +1,110 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
+use crate::api_client::SportsApiClientBuilder;
+use crate::cassette::Cassette;
+use crate::config::Config;
 use crate::error::Error;
-use crate::types::{CompetitorStats, SeasonCompetitors};
+use crate::metrics::Metrics;
+use crate::middleware::{Metered, Quota, RateLimit, Retry, RetryPolicy};
+use crate::quota::Ledger;
+use crate::types::{
+    CompetitionSeasons, CompetitorStats, SeasonCompetitors, SeasonSchedules, SeasonStandings,
+};
+
+#[async_trait]
+pub trait Client: Send + Sync {
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error>;
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error>;
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error>;
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error>;
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error>;
+}
+
+// The stack used by the command line, without the cache. The API client
+// records its responses into a cassette, or replays them, in which case
+// nothing else is needed, not even the API key.
+pub fn from_config(
+    config: &Config,
+    metrics: Arc<Metrics>,
+    ledger: Arc<Ledger>,
+) -> Result<Box<dyn Client>, Error> {
+    let api = SportsApiClientBuilder::from_config(config);
+    if let Some(replay) = &config.replay {
+        return Ok(Box::new(api.cassette(Cassette::replay(replay)?).build()?));
+    }
+
+    let policy = RetryPolicy {
+        retries: config.retries,
+        ..Default::default()
+    };
+    let api = match &config.record {
+        Some(record) => api.cassette(Cassette::record(record)?),
+        None => api,
+    };
+    let api = Quota::new(api.build()?, ledger);
+    let api = Metered::new(api, metrics.clone());
+    let api = Retry::new(RateLimit::new(api, config.rate_limit)?, policy).with_metrics(metrics);
+    Ok(Box::new(api))
+}
+
+macro_rules! delegate_client {
+    ($($wrapper:ident),*) => {$(
+        #[async_trait]
+        impl<C: Client + ?Sized> Client for $wrapper<C> {
+            async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+                (**self).fetch_seasons().await
+            }
+            async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+                (**self).fetch_competitors(season).await
+            }
+            async fn fetch_competitor_stats(
+                &self,
+                season: &str,
+                id: &str,
+            ) -> Result<CompetitorStats, Error> {
+                (**self).fetch_competitor_stats(season, id).await
+            }
+            async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+                (**self).fetch_standings(season).await
+            }
+            async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+                (**self).fetch_schedules(season).await
+            }
+        }
+    )*};
+}
+
+// So boxed and shared clients can be stacked too
+delegate_client!(Box, Arc);
+
+// Fails every fetch, so wrapped in `CachedClient` only the cache is read
+pub struct Offline;
 
 #[async_trait]
-pub trait Client {
-    async fn fetch_competitors(&self) -> Result<SeasonCompetitors, Error>;
-    async fn fetch_competitor_stats(&self, id: &str) -> Result<CompetitorStats, Error>;
+impl Client for Offline {
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        Err(Error::NotCached("seasons".into()))
+    }
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        Err(Error::NotCached(format!("competitors of {season}")))
+    }
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        Err(Error::NotCached(format!("stats of {id} in {season}")))
+    }
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        Err(Error::NotCached(format!("standings of {season}")))
+    }
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        Err(Error::NotCached(format!("schedules of {season}")))
+    }
 }
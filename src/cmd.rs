@@ -1,83 +1,996 @@
-use clap::Parser;
+use std::env;
+use std::io::{self, Write};
+use std::net::IpAddr;
+#[cfg(feature = "export")]
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::cached_client::CachedClient;
-use crate::top_players::TopPlayers;
-use crate::types::Player;
+use anyhow::bail;
+use chrono::{NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use serde_json::json;
+use tracing::{info, warn};
 
-/// CLI tool designed to track the top football players in the Premier League's 23/24 season.
-/// It fetches data from the Sportradar Soccer API and outputs the top 10 players with the most goals and assists.
+use crate::cached_client::{CachedClient, CachedSeason};
+use crate::client::Offline;
+use crate::compare::Comparison;
+use crate::completions::{self, Values};
+use crate::config::{parse_duration, Config, Settings};
+use crate::db::Database;
+use crate::error::Error;
+#[cfg(feature = "export")]
+use crate::export::{self, ExportFormat};
+use crate::fixtures::{FixtureFilter, Fixtures};
+use crate::history::PlayerHistory;
+use crate::league_table::LeagueTable;
+use crate::logging::Logging;
+use crate::matchday::Matchday;
+use crate::output::{write_tables, OutputFormat, Table};
+use crate::quota::{Ledger, Usage};
+use crate::server::{Server, ServerOptions};
+use crate::snapshot::{Snapshot, SnapshotDiff, Snapshots};
+use crate::top_players::{Leaderboard, TopPlayers};
+use crate::top_teams::{TeamColumn, TopTeams};
+use crate::tui::{App, Dashboard};
+use crate::types::{Competitor, MatchStatus, MatchSummary, PlayerStats, Qualifier, StandingType};
+use crate::watch::{LiveEntry, LiveLeaderboard, Movement};
+
+// Moves the cursor to the top left and clears the screen
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// CLI tool designed to track the top football players in the Premier League.
+/// It fetches data from the Sportradar Soccer API and outputs the top players with the most goals and assists.
 #[derive(Parser, Debug)]
+pub struct Cli {
+    #[command(flatten)]
+    pub settings: Settings,
+    #[command(flatten)]
+    pub logging: Logging,
+    #[command(subcommand)]
+    pub cmd: Cmd,
+}
+
+#[derive(Subcommand, Debug)]
 pub enum Cmd {
-    /// Prints the top 10 players ordered by assists
-    TopAssists,
-    /// Prints the top 10 players ordered by goals scored
-    TopGoals,
-    /// Prints the top 10 players ordered first by goals then assists
-    TopPlayers,
-    /// Clears the cache files for the season data
+    /// Prints the top players of the season, by goals, assists or both
+    #[command(subcommand)]
+    Top(TopCmd),
+    /// Prints the season statistics of every team ordered by the chosen column, or of the named ones
+    #[command(alias = "teams")]
+    Team {
+        /// Only teams with this name or abbreviation, such as "City" or "MCI"
+        #[arg(add = Values::Teams.completer())]
+        name: Option<String>,
+        /// Column used to order the teams, goals conceded are ordered from least to most
+        #[arg(long, value_enum, default_value_t = TeamColumn::GoalsScored)]
+        sort_by: TeamColumn,
+    },
+    /// Prints the stats of a player over one or more seasons, including every club they played for
+    #[command(alias = "history")]
+    Player {
+        /// Name or ID of the player
+        #[arg(add = Values::Players.completer())]
+        player: String,
+        /// Season or inclusive range of seasons, such as "23/24" or "21/22..23/24", the configured one by default
+        #[arg(long, add = Values::Seasons.completer())]
+        seasons: Option<String>,
+    },
+    /// Prints a side by side comparison of the season stats of two or more players
+    Compare {
+        /// Names or IDs of the players, "Palmer" and "Cole Palmer" both match "Palmer, Cole"
+        #[arg(required = true, num_args = 2.., add = Values::Players.completer())]
+        players: Vec<String>,
+    },
+    /// Prints the league table with points, results and goals of every team
+    Table {
+        /// Also prints the tables of home and away matches only
+        #[arg(long)]
+        split: bool,
+    },
+    /// Prints the season matches, with the score of the ones already played
+    Fixtures {
+        /// Only matches of the team with this name or abbreviation
+        #[arg(long, add = Values::Teams.completer())]
+        team: Option<String>,
+        /// Only matches from this date on (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Only matches up to this date (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        /// Only matches of this round
+        #[arg(long)]
+        round: Option<usize>,
+        /// Only matches with this status
+        #[arg(long, value_enum)]
+        status: Option<MatchStatus>,
+    },
+    /// Clears, inspects or warms the cache of the season data
+    #[command(subcommand)]
+    Cache(CacheCmd),
+    /// Prints the API calls made this month against the quota budget
+    Quota,
+    /// Serves the leaderboards, teams and players as JSON over HTTP
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: IpAddr,
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// How often the season data older than the cache TTL, or than the interval without one, is fetched again in the background, such as "1h". Off by default
+        #[arg(long, value_parser = parse_interval)]
+        refresh: Option<Duration>,
+    },
+    /// Browses the leaderboards, teams and standings in a terminal UI, with sortable columns, search and player details
+    Tui,
+    /// Saves and lists named snapshots of the season rankings, to compare them later
+    #[command(subcommand)]
+    Snapshot(SnapshotCmd),
+    /// Compares the rankings of two snapshots: rank changes, stat deltas, new entrants and dropouts
+    Diff {
+        /// Name of the older snapshot
+        from: String,
+        /// Name of the newer snapshot
+        to: String,
+        /// Leaderboard to compare
+        #[arg(long, value_enum, default_value_t = Leaderboard::Players)]
+        by: Leaderboard,
+    },
+    /// Stores the seasons, teams and players in a SQLite database, to run queries on them
+    #[command(subcommand)]
+    Db(DbCmd),
+    /// Runs a SQL query on the database written by `db sync`, printing the rows like any other table
+    Query {
+        /// Query to run, such as "SELECT name FROM players", the database is opened read-only
+        sql: String,
+    },
+    /// Writes the stats of every player to a Parquet or Arrow IPC file, with team and season columns
+    #[cfg(feature = "export")]
+    Export {
+        /// File to write, in the format set by --format, parquet or arrow, or else by its extension
+        #[arg(long)]
+        out: PathBuf,
+        /// Season or inclusive range of seasons, such as "23/24" or "21/22..23/24", the configured one by default
+        #[arg(long, add = Values::Seasons.completer())]
+        seasons: Option<String>,
+    },
+    /// Inspects the configuration
+    #[command(subcommand)]
+    Config(ConfigCmd),
+    /// Prints the shell script completing the commands, with the seasons, teams and players in the cache
+    Completions {
+        /// Shell to complete in
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Prints the man page of matchday and its commands, in roff
+    Man,
+    // The names from before the commands were grouped, still accepted
+    #[command(hide = true)]
+    TopAssists(TopArgs),
+    #[command(hide = true)]
+    TopGoals(TopArgs),
+    #[command(hide = true)]
+    TopPlayers(TopArgs),
+    #[command(hide = true)]
     ClearCache,
+    #[command(hide = true)]
+    WarmCache {
+        #[arg(long)]
+        force: bool,
+    },
 }
 
-async fn load_players(mut cache: CachedClient) -> anyhow::Result<Vec<Player>> {
-    println!("Fetching season data...");
-    let competitors = cache.get_competitors().await?;
-    let mut players = Vec::with_capacity(20 * 28);
+#[derive(Subcommand, Debug)]
+pub enum TopCmd {
+    /// Prints the top players ordered by goals scored
+    Goals(TopArgs),
+    /// Prints the top players ordered by assists
+    Assists(TopArgs),
+    /// Prints the top players ordered first by goals then assists
+    Players(TopArgs),
+}
 
-    for competitor in competitors.season_competitors.clone() {
-        let stats = cache.get_competitor_stats(&competitor.id).await?;
-        players.extend(stats.competitor.players.clone());
-    }
+#[derive(Args, Debug)]
+pub struct TopArgs {
+    /// Lists players who moved mid season once for each club, instead of summing their stats
+    #[arg(long)]
+    pub split_by_team: bool,
+    /// Redraws the leaderboard every interval, such as "60s", marking rank changes and new goals or assists
+    #[arg(long, value_parser = parse_watch_interval)]
+    pub watch: Option<Duration>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCmd {
+    /// Clears the cache files for the season data
+    Clear,
+    /// Prints the cached seasons, with their teams, standings, schedules and age
+    Status,
+    /// Fetches the season data again into the cache, if the calls it needs fit in the quota budget
+    Warm {
+        /// Warms the cache even if it goes over the quota budget
+        #[arg(long)]
+        force: bool,
+    },
+}
 
-    Ok(players)
+#[derive(Subcommand, Debug)]
+pub enum DbCmd {
+    /// Writes the seasons, and the teams and players of the synced seasons with their stats
+    Sync {
+        /// Season or inclusive range of seasons, such as "23/24" or "21/22..23/24", the configured one by default
+        #[arg(long, add = Values::Seasons.completer())]
+        seasons: Option<String>,
+    },
 }
 
-fn top_assists(top_players: TopPlayers) {
-    println!("Assists | Player Name");
+#[derive(Subcommand, Debug)]
+pub enum SnapshotCmd {
+    /// Saves the players of the season with their stats, from the cache if they're cached
+    Save {
+        /// Name of the snapshot, such as "gw10"
+        name: String,
+        /// Replaces the snapshot with the same name
+        #[arg(long)]
+        force: bool,
+    },
+    /// Lists the saved snapshots, from the oldest to the newest
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCmd {
+    /// Prints the effective value of every setting and where it came from
+    Show,
+}
+
+fn top_assists(top_players: TopPlayers) -> Table {
+    let mut table = Table::new(&["Assists", "Player Name", "Team"]);
     for player in top_players.by_assists() {
-        println!(" {} | {}", player.statistics.assists, player.name);
+        table.push(vec![
+            json!(player.statistics.assists),
+            json!(player.name),
+            json!(player.team()),
+        ]);
     }
+    table
 }
 
-fn top_goals(top_players: TopPlayers) {
-    println!("Goals | Player Name");
+fn top_goals(top_players: TopPlayers) -> Table {
+    let mut table = Table::new(&["Goals", "Player Name", "Team"]);
     for player in top_players.by_goals() {
-        println!(" {} | {}", player.statistics.goals_scored, player.name);
+        table.push(vec![
+            json!(player.statistics.goals_scored),
+            json!(player.name),
+            json!(player.team()),
+        ]);
     }
+    table
 }
 
-fn top_players(top_players: TopPlayers) {
-    println!("Goals | Assists | Player Name");
+fn top_players(top_players: TopPlayers) -> Table {
+    let mut table = Table::new(&["Goals", "Assists", "Player Name", "Team"]);
     for player in top_players.by_both() {
-        println!(
-            " {} | {} | {}",
-            player.statistics.goals_scored, player.statistics.assists, player.name
-        );
+        table.push(vec![
+            json!(player.statistics.goals_scored),
+            json!(player.statistics.assists),
+            json!(player.name),
+            json!(player.team()),
+        ]);
     }
+    table
 }
 
-impl Cmd {
+pub(crate) fn top_teams(teams: Vec<Competitor>) -> Table {
+    let mut table = Table::new(&[
+        "Played",
+        "Poss%",
+        "GF",
+        "GF 1H",
+        "GF 2H",
+        "GA",
+        "GA 1H",
+        "GA 2H",
+        "Shots",
+        "On Target",
+        "Corners",
+        "Cards",
+        "Head",
+        "Foot",
+        "Team Name",
+    ]);
+    for team in teams {
+        let stats = team.statistics;
+        table.push(vec![
+            json!(stats.matches_played),
+            json!((stats.average_ball_possession * 10.0).round() / 10.0),
+            json!(stats.goals_scored),
+            json!(stats.goals_scored_first_half),
+            json!(stats.goals_scored_second_half),
+            json!(stats.goals_conceded),
+            json!(stats.goals_conceded_first_half),
+            json!(stats.goals_conceded_second_half),
+            json!(stats.shots_total),
+            json!(stats.shots_on_target),
+            json!(stats.corner_kicks),
+            json!(stats.cards_given),
+            json!(stats.goals_by_head),
+            json!(stats.goals_by_foot),
+            json!(team.name),
+        ]);
+    }
+    table
+}
+
+pub(crate) fn league_table(league_table: LeagueTable, split: bool) -> Vec<Table> {
+    let mut kinds = vec![("Total", StandingType::Total)];
+    if split {
+        kinds.push(("Home", StandingType::Home));
+        kinds.push(("Away", StandingType::Away));
+    }
+
+    kinds
+        .into_iter()
+        .map(|(title, kind)| {
+            let mut table = Table::new(&[
+                "Pos",
+                "P",
+                "W",
+                "D",
+                "L",
+                "GF",
+                "GA",
+                "GD",
+                "Pts",
+                "Team Name",
+            ]);
+            if split {
+                table = table.with_title(title);
+            }
+            for row in league_table.rows(kind) {
+                table.push(vec![
+                    json!(row.rank),
+                    json!(row.played),
+                    json!(row.win),
+                    json!(row.draw),
+                    json!(row.loss),
+                    json!(row.goals_for),
+                    json!(row.goals_against),
+                    json!(row.goals_diff),
+                    json!(row.points),
+                    json!(row.competitor.name),
+                ]);
+            }
+            table
+        })
+        .collect()
+}
+
+fn fixtures(fixtures: Vec<MatchSummary>) -> Table {
+    let mut table = Table::new(&["Round", "Kickoff (UTC)", "Status", "Home", "Score", "Away"]);
+    for summary in fixtures {
+        let name = |qualifier| {
+            summary
+                .competitor(qualifier)
+                .map_or("", |competitor| competitor.name.as_str())
+        };
+        let status = &summary.sport_event_status;
+        let score = match (status.home_score, status.away_score) {
+            (Some(home), Some(away)) => json!(format!("{home} - {away}")),
+            _ => json!(null),
+        };
+        table.push(vec![
+            json!(summary.round()),
+            json!(summary
+                .sport_event
+                .start_time
+                .format("%Y-%m-%d %H:%M")
+                .to_string()),
+            json!(status
+                .status
+                .to_possible_value()
+                .map_or("unknown".into(), |value| value.get_name().to_string())),
+            json!(name(Qualifier::Home)),
+            score,
+            json!(name(Qualifier::Away)),
+        ]);
+    }
+    table
+}
+
+fn comparison(comparison: Comparison) -> Table {
+    let mut headers = vec!["Stat"];
+    headers.extend(comparison.players.iter().map(|p| p.name.as_str()));
+    let mut table =
+        Table::new(&headers).with_note("* best value, (pN) percentile rank within the league");
+    for row in comparison.rows {
+        let mut values = vec![json!(row.label)];
+        values.extend(row.values.iter().map(|compared| {
+            let value = match compared.value {
+                Some(value) if row.per_match => format!("{value:.2}"),
+                Some(value) => format!("{value}"),
+                None => "-".into(),
+            };
+            let best = if compared.best { "*" } else { "" };
+            match compared.percentile {
+                Some(percentile) => json!(format!("{value}{best} (p{percentile})")),
+                None => json!(format!("{value}{best}")),
+            }
+        }));
+        table.push(values);
+    }
+    table
+}
+
+fn player_history(history: PlayerHistory) -> Table {
+    let mut table = Table::new(&[
+        "Season",
+        "Team",
+        "Matches",
+        "Goals",
+        "Assists",
+        "Shots on Target",
+        "Yellow Cards",
+    ])
+    .with_title(history.name.clone());
+    let mut row = |season: &str, team: &str, stats: &PlayerStats| {
+        table.push(vec![
+            json!(season),
+            json!(team),
+            json!(stats.matches_played),
+            json!(stats.goals_scored),
+            json!(stats.assists),
+            json!(stats.shots_on_target),
+            json!(stats.yellow_cards),
+        ]);
+    };
+
+    for entry in &history.entries {
+        row(&entry.season, &entry.team, &entry.statistics);
+    }
+    row("Total", &history.teams().join(", "), &history.total());
+    table
+}
+
+fn snapshots(snapshots: Vec<Snapshot>) -> Table {
+    let mut table = Table::new(&["Name", "Season", "Taken At (UTC)", "Players"]);
+    for snapshot in snapshots {
+        table.push(vec![
+            json!(snapshot.name),
+            json!(snapshot.season),
+            json!(snapshot.taken_at.format("%Y-%m-%d %H:%M").to_string()),
+            json!(snapshot.players.len()),
+        ]);
+    }
+    table
+}
+
+fn snapshot_diff(
+    from: &Snapshot,
+    to: &Snapshot,
+    leaderboard: Leaderboard,
+    limit: usize,
+    format: OutputFormat,
+) -> Result<Vec<Table>, Error> {
+    let diff = SnapshotDiff::new(from, to, leaderboard, limit)?;
+    let taken_at = |snapshot: &Snapshot| snapshot.taken_at.format("%Y-%m-%d %H:%M").to_string();
+    let rankings = live_table(leaderboard, &diff.entries, format)
+        .with_title("Rankings")
+        .with_note(format!(
+            "Changes from {} ({} UTC) to {} ({} UTC)",
+            from.name,
+            taken_at(from),
+            to.name,
+            taken_at(to)
+        ));
+
+    let mut dropouts =
+        Table::new(&["Rank", "Goals", "Assists", "Player Name", "Team"]).with_title("Dropouts");
+    for (rank, player) in diff.dropouts {
+        dropouts.push(vec![
+            json!(rank),
+            json!(player.statistics.goals_scored),
+            json!(player.statistics.assists),
+            json!(player.name),
+            json!(player.team()),
+        ]);
+    }
+    Ok(vec![rankings, dropouts])
+}
+
+fn quota(usage: Usage) -> Vec<Table> {
+    let mut summary = Table::new(&["Month", "Calls", "Budget", "Remaining"]).with_title("Quota");
+    summary.push(vec![
+        json!(usage.month),
+        json!(usage.calls()),
+        json!(usage.budget),
+        json!(usage.remaining()),
+    ]);
+    let mut endpoints = Table::new(&["Endpoint", "Calls"]).with_title("Calls by Endpoint");
+    for (endpoint, calls) in &usage.endpoints {
+        endpoints.push(vec![json!(endpoint), json!(calls)]);
+    }
+    vec![summary, endpoints]
+}
+
+fn cache_status(seasons: Vec<CachedSeason>, config: &Config) -> Table {
+    let mut table = Table::new(&[
+        "Season",
+        "Year",
+        "Teams",
+        "Standings",
+        "Schedules",
+        "Oldest (UTC)",
+        "Expired",
+    ])
+    .with_note(format!("Cached in {}", config.cache_dir.display()));
+    for season in seasons {
+        table.push(vec![
+            json!(season.id),
+            json!(season.year),
+            json!(season.teams),
+            json!(season.standings),
+            json!(season.schedules),
+            json!(season
+                .updated_at
+                .map(|updated_at| updated_at.format("%Y-%m-%d %H:%M").to_string())),
+            json!(season.expired),
+        ]);
+    }
+    table
+}
+
+fn effective_config(config: &Config) -> Table {
+    let mut table = Table::new(&["Setting", "Value", "Source"]);
+    for (key, value, source) in config.effective() {
+        table.push(vec![json!(key), json!(value), json!(source)]);
+    }
+    table
+}
+
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    parse_duration(value)
+        .ok_or_else(|| "invalid duration, such as \"90s\", \"30m\" or \"6h\"".to_string())
+}
+
+fn parse_watch_interval(value: &str) -> Result<Duration, String> {
+    match parse_interval(value)? {
+        every if every.is_zero() => Err("the interval can't be 0".to_string()),
+        every => Ok(every),
+    }
+}
+
+fn paint(text: String, code: &str, color: bool) -> String {
+    if color && !text.is_empty() {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text
+    }
+}
+
+// Marks the rank changes and new goals or assists with arrows and colors in
+// the table format, and as separate numbers in JSON
+fn live_table(leaderboard: Leaderboard, entries: &[LiveEntry], format: OutputFormat) -> Table {
+    let styled = format == OutputFormat::Table;
+    let color = styled && env::var_os("NO_COLOR").is_none();
+    let stats: &[&str] = match leaderboard {
+        Leaderboard::Goals => &["Goals"],
+        Leaderboard::Assists => &["Assists"],
+        Leaderboard::Players => &["Goals", "Assists"],
+    };
+
+    let mut headers = vec!["Rank", "Change"];
+    headers.extend(stats);
+    if !styled {
+        headers.extend(stats.iter().map(|&stat| match stat {
+            "Goals" => "New Goals",
+            _ => "New Assists",
+        }));
+    }
+    headers.extend(["Player Name", "Team"]);
+
+    let mut table = Table::new(&headers);
+    for entry in entries {
+        let mut row = vec![json!(entry.rank)];
+        row.push(match (entry.movement, styled) {
+            (Movement::Up(by), true) => json!(paint(format!("▲{by}"), "32", color)),
+            (Movement::Down(by), true) => json!(paint(format!("▼{by}"), "31", color)),
+            (Movement::New, true) => json!(paint("new".into(), "32", color)),
+            (Movement::Same, true) => json!(""),
+            (Movement::Up(by), false) => json!(by),
+            (Movement::Down(by), false) => json!(-(by as i64)),
+            (Movement::New, false) => json!(null),
+            (Movement::Same, false) => json!(0),
+        });
+        let values: Vec<(usize, usize)> = stats
+            .iter()
+            .map(|&stat| match stat {
+                "Goals" => (entry.player.statistics.goals_scored, entry.new_goals),
+                _ => (entry.player.statistics.assists, entry.new_assists),
+            })
+            .collect();
+        if styled {
+            row.extend(values.iter().map(|&(value, new)| match new {
+                0 => json!(value),
+                new => json!(paint(format!("{value} (+{new})"), "1;32", color)),
+            }));
+        } else {
+            row.extend(values.iter().map(|&(value, _)| json!(value)));
+            row.extend(values.iter().map(|&(_, new)| json!(new)));
+        }
+        row.push(json!(entry.player.name));
+        row.push(json!(entry.player.team()));
+        table.push(row);
+    }
+    table
+}
+
+// Redraws the leaderboard in place until interrupted, or prints it again in
+// JSON. Each refresh only fetches the stats of the teams with a match in play
+// according to the schedules, read once at the start, once they're older than
+// the cache TTL or the interval without one, and stops once they don't fit in
+// the quota budget.
+async fn watch(
+    config: &Config,
+    out: &mut impl Write,
+    leaderboard: Leaderboard,
+    split_by_team: bool,
+    every: Duration,
+) -> anyhow::Result<()> {
+    let matchday = matchday(config)?;
+    let season = matchday.season_id(&config.season).await?;
+    let fixtures = Fixtures::new(
+        matchday
+            .fixtures(&season, &FixtureFilter::default())
+            .await?,
+    );
+    let max_age = matchday.cache_ttl().unwrap_or(every);
+    let mut live = LiveLeaderboard::new();
+
+    for refresh in 0.. {
+        if refresh > 0 {
+            let teams = fixtures.teams_in_play(Utc::now());
+            let calls = matchday.stale_stats_calls(&season, &teams, max_age).await?;
+            if calls > 0 {
+                if let Some(Err(e)) = matchday.quota()?.map(|usage| usage.check(calls)) {
+                    bail!("stopped refreshing the leaderboard, it {e}");
+                }
+                if let Err(e) = matchday.refresh_stale_stats(&season, &teams, max_age).await {
+                    warn!(error = %e, "failed to refresh the team stats");
+                }
+            }
+        }
+        match matchday.players(&season, split_by_team).await {
+            Ok(players) => {
+                let ranked = leaderboard.rank(players.clone(), config.limit);
+                let entries = live.update(&players, ranked);
+                let mut table = live_table(leaderboard, &entries, config.format);
+                if config.format == OutputFormat::Table {
+                    write!(out, "{CLEAR_SCREEN}")?;
+                    table = table.with_title(format!(
+                        "Updated at {} UTC, every {}s",
+                        Utc::now().format("%H:%M:%S"),
+                        every.as_secs()
+                    ));
+                }
+                write_tables(out, config.format, &[table])?;
+                out.flush()?;
+            }
+            Err(e) if refresh == 0 => return Err(e.into()),
+            // The previous leaderboard stays on screen
+            Err(e) => warn!(error = %e, "failed to refresh the leaderboard"),
+        }
+        tokio::time::sleep(every).await;
+    }
+
+    Ok(())
+}
+
+// Reads only the cache with --offline
+fn matchday(config: &Config) -> Result<Matchday, Error> {
+    if config.offline {
+        Ok(Matchday::offline(config))
+    } else {
+        Matchday::new(config)
+    }
+}
+
+impl Cli {
+    // Answers the completion requests of the shell scripts and exits, before
+    // the command line is parsed. Does nothing otherwise.
+    pub fn complete() {
+        completions::complete();
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
-        let mut cache = CachedClient::new()?;
+        self.logging.init();
+        let config = Config::load(self.settings)?;
+        self.cmd.run(config, &mut io::stdout()).await
+    }
+}
+
+impl Cmd {
+    // Results are written to `out`, progress messages are logged. Every
+    // command is matched here, the names from before the commands were
+    // grouped along with the grouped ones.
+    pub async fn run(self, config: Config, out: &mut impl Write) -> anyhow::Result<()> {
+        // Before fetching anything
+        #[cfg(feature = "export")]
+        if matches!(config.format, OutputFormat::Parquet | OutputFormat::Arrow)
+            && !matches!(self, Cmd::Export { .. })
+        {
+            bail!(
+                "the {} format is only written by export",
+                config.format.as_str()
+            );
+        }
+        let season = config.season.as_str();
 
         match self {
-            Cmd::TopAssists => {
-                let players = load_players(cache).await?;
-                top_assists(TopPlayers::new(players));
+            // Neither reads the config
+            Cmd::Completions { shell } => completions::write_script(out, shell),
+            Cmd::Man => completions::write_man_page(out),
+            Cmd::Config(ConfigCmd::Show) => {
+                write_tables(out, config.format, &[effective_config(&config)])?;
+                Ok(())
+            }
+            // Only the cache dir is read, without the API key either
+            Cmd::Cache(CacheCmd::Status) => {
+                let status = CachedClient::from_config(Offline, &config).status()?;
+                write_tables(out, config.format, &[cache_status(status, &config)])?;
+                Ok(())
+            }
+            // Without the API key, the calls are only read from the ledger
+            Cmd::Quota => {
+                let tables = quota(Ledger::from_config(&config).usage()?);
+                write_tables(out, config.format, &tables)?;
+                Ok(())
+            }
+            // Snapshots are only read, so neither needs the API key either
+            Cmd::Snapshot(SnapshotCmd::List) => {
+                let tables = [snapshots(Snapshots::new(&config.cache_dir).list()?)];
+                write_tables(out, config.format, &tables)?;
+                Ok(())
+            }
+            Cmd::Diff { from, to, by } => {
+                let saved = Snapshots::new(&config.cache_dir);
+                let (from, to) = (saved.load(&from)?, saved.load(&to)?);
+                let tables = snapshot_diff(&from, &to, by, config.limit, config.format)?;
+                write_tables(out, config.format, &tables)?;
+                Ok(())
+            }
+            // Only reads the database, the API isn't called
+            Cmd::Query { sql } => {
+                let tables = [Database::open_read_only(&Database::file(&config))?.query(&sql)?];
+                write_tables(out, config.format, &tables)?;
+                Ok(())
+            }
+            Cmd::Serve {
+                host,
+                port,
+                refresh,
+            } => {
+                let options = ServerOptions {
+                    season: config.season.clone(),
+                    limit: config.limit,
+                    refresh: refresh.filter(|refresh| !refresh.is_zero()),
+                };
+                let mut server =
+                    Server::bind((host, port).into(), matchday(&config)?, options).await?;
+                info!("Serving on {}", server.url());
+                server.wait().await;
+                Ok(())
+            }
+            Cmd::Tui => {
+                info!("Fetching season data...");
+                let dashboard = Dashboard::load(&matchday(&config)?, season).await?;
+                App::new(dashboard).run()?;
+                Ok(())
+            }
+            Cmd::Top(TopCmd::Goals(args)) | Cmd::TopGoals(args) => {
+                top(&config, out, Leaderboard::Goals, args).await
+            }
+            Cmd::Top(TopCmd::Assists(args)) | Cmd::TopAssists(args) => {
+                top(&config, out, Leaderboard::Assists, args).await
             }
-            Cmd::TopGoals => {
-                let players = load_players(cache).await?;
-                top_goals(TopPlayers::new(players));
+            Cmd::Top(TopCmd::Players(args)) | Cmd::TopPlayers(args) => {
+                top(&config, out, Leaderboard::Players, args).await
             }
-            Cmd::TopPlayers => {
-                let players = load_players(cache).await?;
-                top_players(TopPlayers::new(players));
+            Cmd::Team { name, sort_by } => {
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    let teams = match name {
+                        Some(name) => TopTeams::new(matchday.teams(season).await?)
+                            .named(&name)?
+                            .sort_by(sort_by)
+                            .collect(),
+                        None => matchday.top_teams(season, sort_by).await?,
+                    };
+                    Ok(vec![top_teams(teams)])
+                })
+                .await
             }
-            Cmd::ClearCache => {
-                println!("Deleting season data");
-                cache.clear()?;
+            Cmd::Table { split } => {
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    let standings = matchday.standings(season).await?;
+                    Ok(league_table(standings, split))
+                })
+                .await
+            }
+            Cmd::Fixtures {
+                team,
+                from,
+                to,
+                round,
+                status,
+            } => {
+                let filter = FixtureFilter {
+                    team,
+                    from,
+                    to,
+                    round,
+                    status,
+                };
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    Ok(vec![fixtures(matchday.fixtures(season, &filter).await?)])
+                })
+                .await
+            }
+            Cmd::Compare { players } => {
+                let players: Vec<&str> = players.iter().map(String::as_str).collect();
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    Ok(vec![comparison(matchday.compare(season, &players).await?)])
+                })
+                .await
+            }
+            Cmd::Player { player, seasons } => {
+                let seasons = seasons.as_deref().unwrap_or(season);
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    Ok(vec![player_history(
+                        matchday.history(&player, seasons).await?,
+                    )])
+                })
+                .await
+            }
+            Cmd::Cache(CacheCmd::Clear) | Cmd::ClearCache => {
+                fetch(&config, out, async |matchday| {
+                    info!("Deleting season data");
+                    matchday.clear_cache()?;
+                    Ok(vec![])
+                })
+                .await
+            }
+            Cmd::Cache(CacheCmd::Warm { force }) | Cmd::WarmCache { force } => {
+                if config.offline {
+                    bail!("warming the cache fetches the season data, it can't be done --offline");
+                }
+                fetch(&config, out, async |matchday| {
+                    let calls = matchday.refresh_calls(season).await?;
+                    let usage = matchday.quota()?;
+                    if let Some(Err(e)) = usage.as_ref().map(|usage| usage.check(calls)) {
+                        if !force {
+                            bail!("warming the cache {e}, use --force to warm it anyway");
+                        }
+                        warn!("Warming the cache {e}, warming it anyway");
+                    }
+                    match usage.and_then(|usage| usage.remaining()) {
+                        Some(remaining) => info!(
+                            "Warming the cache with up to {calls} API calls, {remaining} left this month"
+                        ),
+                        None => info!("Warming the cache with up to {calls} API calls"),
+                    }
+                    matchday.refresh(season).await?;
+                    Ok(vec![])
+                })
+                .await
+            }
+            Cmd::Snapshot(SnapshotCmd::Save { name, force }) => {
+                let saved = Snapshots::new(&config.cache_dir);
+                // Before fetching anything
+                if !force && saved.exists(&name)? {
+                    return Err(Error::SnapshotExists(name).into());
+                }
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    let snapshot = matchday.snapshot(season, &name).await?;
+                    saved.save(&snapshot, force)?;
+                    info!(
+                        "Saved snapshot {name} with {} players",
+                        snapshot.players.len()
+                    );
+                    Ok(vec![])
+                })
+                .await
+            }
+            Cmd::Db(DbCmd::Sync { seasons }) => {
+                let file = Database::file(&config);
+                let mut db = Database::open(&file)?;
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    let synced = matchday
+                        .sync(&mut db, seasons.as_deref().unwrap_or(season))
+                        .await?;
+                    info!(
+                        "Synced {} seasons, {} teams and {} players into {}",
+                        synced.seasons,
+                        synced.competitors,
+                        synced.players,
+                        file.display()
+                    );
+                    Ok(vec![])
+                })
+                .await
+            }
+            #[cfg(feature = "export")]
+            Cmd::Export { out: file, seasons } => {
+                let format = match config.format {
+                    OutputFormat::Parquet => ExportFormat::Parquet,
+                    OutputFormat::Arrow => ExportFormat::Arrow,
+                    OutputFormat::Table | OutputFormat::Json => ExportFormat::from_file(&file)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "export writes parquet or arrow files, set one with --format"
+                            )
+                        })?,
+                };
+                fetch(&config, out, async |matchday| {
+                    info!("Fetching season data...");
+                    let seasons = matchday
+                        .seasons_players(seasons.as_deref().unwrap_or(season))
+                        .await?;
+                    let batch = export::record_batch(&seasons)?;
+                    export::write(&file, format, &batch)?;
+                    info!("Exported {} rows to {}", batch.num_rows(), file.display());
+                    Ok(vec![])
+                })
+                .await
             }
         }
+    }
+}
+
+// A leaderboard, watched if it's redrawn every interval
+async fn top(
+    config: &Config,
+    out: &mut impl Write,
+    leaderboard: Leaderboard,
+    args: TopArgs,
+) -> anyhow::Result<()> {
+    if let Some(every) = args.watch {
+        return watch(config, out, leaderboard, args.split_by_team, every).await;
+    }
+    fetch(config, out, async |matchday| {
+        info!("Fetching season data...");
+        let players = matchday.players(&config.season, args.split_by_team).await?;
+        let players = TopPlayers::new(players, config.limit);
+        Ok(vec![match leaderboard {
+            Leaderboard::Goals => top_goals(players),
+            Leaderboard::Assists => top_assists(players),
+            Leaderboard::Players => top_players(players),
+        }])
+    })
+    .await
+}
 
-        Ok(())
+// Runs a command on the season data, from the cache or the API. The metrics
+// file is written even if it failed, the failed requests count too.
+async fn fetch(
+    config: &Config,
+    out: &mut impl Write,
+    tables: impl AsyncFnOnce(&Matchday) -> anyhow::Result<Vec<Table>>,
+) -> anyhow::Result<()> {
+    let matchday = matchday(config)?;
+    let tables = tables(&matchday).await;
+    if let Some(metrics_file) = &config.metrics_file {
+        matchday.metrics().write_file(metrics_file)?;
     }
+    write_tables(out, config.format, &tables?)?;
+    Ok(())
 }
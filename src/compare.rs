@@ -0,0 +1,300 @@
+use crate::players::SeasonPlayer;
+use crate::types::PlayerStats;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Better {
+    Higher,
+    Lower,
+}
+
+struct StatDefinition {
+    label: &'static str,
+    per_match: bool,
+    better: Better,
+    value: fn(&PlayerStats) -> usize,
+}
+
+const STATS: &[StatDefinition] = &[
+    StatDefinition {
+        label: "Matches",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.matches_played,
+    },
+    StatDefinition {
+        label: "Goals",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.goals_scored,
+    },
+    StatDefinition {
+        label: "Assists",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.assists,
+    },
+    StatDefinition {
+        label: "Goals + Assists",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.goals_scored + s.assists,
+    },
+    StatDefinition {
+        label: "Penalty Goals",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.goals_by_penalty,
+    },
+    StatDefinition {
+        label: "Headed Goals",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.goals_by_head,
+    },
+    StatDefinition {
+        label: "Shots",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.shots_on_target + s.shots_off_target + s.shots_blocked,
+    },
+    StatDefinition {
+        label: "Shots on Target",
+        per_match: false,
+        better: Better::Higher,
+        value: |s| s.shots_on_target,
+    },
+    StatDefinition {
+        label: "Yellow Cards",
+        per_match: false,
+        better: Better::Lower,
+        value: |s| s.yellow_cards,
+    },
+    StatDefinition {
+        label: "Red Cards",
+        per_match: false,
+        better: Better::Lower,
+        value: |s| s.red_cards + s.yellow_red_cards,
+    },
+    StatDefinition {
+        label: "Goals/Match",
+        per_match: true,
+        better: Better::Higher,
+        value: |s| s.goals_scored,
+    },
+    StatDefinition {
+        label: "Assists/Match",
+        per_match: true,
+        better: Better::Higher,
+        value: |s| s.assists,
+    },
+    StatDefinition {
+        label: "Goals + Assists/Match",
+        per_match: true,
+        better: Better::Higher,
+        value: |s| s.goals_scored + s.assists,
+    },
+    StatDefinition {
+        label: "Shots/Match",
+        per_match: true,
+        better: Better::Higher,
+        value: |s| s.shots_on_target + s.shots_off_target + s.shots_blocked,
+    },
+];
+
+impl StatDefinition {
+    // Rates are per match played, as the API doesn't report the minutes.
+    // Unknown for players without any match.
+    fn value(&self, stats: &PlayerStats) -> Option<f64> {
+        let value = (self.value)(stats) as f64;
+        if !self.per_match {
+            Some(value)
+        } else if stats.matches_played > 0 {
+            Some(value / stats.matches_played as f64)
+        } else {
+            None
+        }
+    }
+
+    fn is_better_or_equal(&self, a: f64, b: f64) -> bool {
+        match self.better {
+            Better::Higher => a >= b,
+            Better::Lower => a <= b,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparedValue {
+    pub value: Option<f64>,
+    pub best: bool,
+    // Share of the league's players this one is better than or equal to
+    pub percentile: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonRow {
+    pub label: &'static str,
+    pub per_match: bool,
+    pub values: Vec<ComparedValue>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub players: Vec<SeasonPlayer>,
+    pub rows: Vec<ComparisonRow>,
+}
+
+// Only players that took part in at least a match are ranked
+pub fn compare(players: &[SeasonPlayer], league: &[SeasonPlayer]) -> Vec<ComparisonRow> {
+    let league: Vec<&PlayerStats> = league
+        .iter()
+        .map(|p| &p.statistics)
+        .filter(|s| s.matches_played > 0)
+        .collect();
+
+    STATS
+        .iter()
+        .map(|stat| {
+            let league_values: Vec<f64> = league.iter().filter_map(|s| stat.value(s)).collect();
+            let values: Vec<Option<f64>> =
+                players.iter().map(|p| stat.value(&p.statistics)).collect();
+            let known: Vec<f64> = values.iter().flatten().copied().collect();
+            let all_equal = known.windows(2).all(|w| w[0] == w[1]);
+
+            ComparisonRow {
+                label: stat.label,
+                per_match: stat.per_match,
+                values: values
+                    .iter()
+                    .map(|value| ComparedValue {
+                        value: *value,
+                        best: value.is_some_and(|v| {
+                            !all_equal
+                                && known.iter().all(|&other| stat.is_better_or_equal(v, other))
+                        }),
+                        percentile: value.filter(|_| !league_values.is_empty()).map(|v| {
+                            let below = league_values
+                                .iter()
+                                .filter(|&&other| stat.is_better_or_equal(v, other))
+                                .count();
+                            (below * 100) / league_values.len()
+                        }),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compare::compare;
+    use crate::players::{find_player, season_players, SeasonPlayer};
+    use crate::types::{CompetitorStats, PlayerStats};
+
+    fn mock_player(goals: usize, yellow_cards: usize, matches: usize) -> SeasonPlayer {
+        SeasonPlayer {
+            id: Default::default(),
+            name: Default::default(),
+            teams: Default::default(),
+            statistics: PlayerStats {
+                goals_scored: goals,
+                yellow_cards,
+                matches_played: matches,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let league = vec![
+            mock_player(22, 8, 34),
+            mock_player(16, 3, 35),
+            mock_player(5, 1, 20),
+            mock_player(0, 0, 0),
+        ];
+        let rows = compare(&league[0..2], &league);
+
+        let goals = rows.iter().find(|row| row.label == "Goals").unwrap();
+        assert_eq!(
+            goals
+                .values
+                .iter()
+                .map(|v| (v.value, v.best, v.percentile))
+                .collect::<Vec<_>>(),
+            vec![(Some(22.0), true, Some(100)), (Some(16.0), false, Some(66))]
+        );
+
+        // fewer cards is better
+        let cards = rows.iter().find(|row| row.label == "Yellow Cards").unwrap();
+        assert_eq!(
+            cards.values.iter().map(|v| v.best).collect::<Vec<_>>(),
+            vec![false, true]
+        );
+        assert_eq!(cards.values[0].percentile, Some(33));
+
+        // the player without matches isn't ranked
+        let per_match = rows.iter().find(|row| row.label == "Goals/Match").unwrap();
+        assert_eq!(per_match.values[1].value, Some(16.0 / 35.0));
+        assert_eq!(per_match.values[1].percentile, Some(66));
+    }
+
+    #[test]
+    fn test_compare_without_matches() {
+        let players = vec![mock_player(0, 0, 0), mock_player(0, 0, 0)];
+        let rows = compare(&players, &players);
+
+        let goals = rows.iter().find(|row| row.label == "Goals").unwrap();
+        assert!(goals.values.iter().all(|v| !v.best));
+
+        let per_match = rows.iter().find(|row| row.label == "Goals/Match").unwrap();
+        assert!(per_match
+            .values
+            .iter()
+            .all(|v| v.value.is_none() && v.percentile.is_none()));
+    }
+
+    // From the stats the API returns, which only have matches played
+    #[test]
+    fn test_compare_fixtures() {
+        let teams = [
+            include_str!(
+                "../fixtures/seasons/sr_season_105353/competitors/sr_competitor_17/statistics.json"
+            ),
+            include_str!(
+                "../fixtures/seasons/sr_season_105353/competitors/sr_competitor_38/statistics.json"
+            ),
+            include_str!(
+                "../fixtures/seasons/sr_season_105353/competitors/sr_competitor_42/statistics.json"
+            ),
+        ]
+        .into_iter()
+        .map(|json| {
+            serde_json::from_str::<CompetitorStats>(json)
+                .unwrap()
+                .competitor
+        })
+        .collect();
+        let league = season_players(teams, false);
+        let players = [
+            find_player(&league, "palmer").unwrap().clone(),
+            find_player(&league, "jackson").unwrap().clone(),
+        ];
+        let rows = compare(&players, &league);
+
+        let per_match = rows.iter().find(|row| row.label == "Goals/Match").unwrap();
+        assert_eq!(
+            per_match
+                .values
+                .iter()
+                .map(|v| (v.value, v.best))
+                .collect::<Vec<_>>(),
+            vec![(Some(22.0 / 34.0), true), (Some(14.0 / 35.0), false)]
+        );
+        assert!(rows
+            .iter()
+            .filter(|row| row.per_match)
+            .all(|row| row.values.iter().all(|v| v.value.is_some())));
+    }
+}
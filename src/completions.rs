@@ -0,0 +1,181 @@
+use std::io::Write;
+
+use clap::CommandFactory;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+use clap_complete::{ArgValueCandidates, CompleteEnv, CompletionCandidate, Shell};
+
+use crate::client::Client;
+use crate::cmd::Cli;
+use crate::config::{Config, Settings};
+use crate::matchday::Matchday;
+
+// Env var the shell sets to ask matchday for the completions of a command line
+const COMPLETE_VAR: &str = "COMPLETE";
+
+// Values of an argument completed by the shell, drawn from the cache
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Values {
+    // Years, with the IDs only once "sr:" is typed
+    Seasons,
+    // Names of the teams of the season
+    Teams,
+    // Names of the players of the season, with the IDs only once "sr:" is typed
+    Players,
+}
+
+impl Values {
+    // Nothing is fetched, the values that aren't cached aren't completed
+    pub async fn candidates<C: Client>(
+        self,
+        matchday: &Matchday<C>,
+        season: &str,
+    ) -> Vec<CompletionCandidate> {
+        let mut candidates = Vec::new();
+        match self {
+            Values::Seasons => {
+                for season in matchday.seasons().await.unwrap_or_default() {
+                    candidates.push(
+                        CompletionCandidate::new(&season.year)
+                            .help(Some(season.name.clone().into())),
+                    );
+                    candidates.push(
+                        CompletionCandidate::new(&season.id)
+                            .help(Some(season.name.into()))
+                            .hide(true),
+                    );
+                }
+            }
+            Values::Teams => {
+                for team in matchday.teams(season).await.unwrap_or_default() {
+                    candidates.push(
+                        CompletionCandidate::new(team.name).help(Some(team.abbreviation.into())),
+                    );
+                }
+            }
+            Values::Players => {
+                for player in matchday.players(season, false).await.unwrap_or_default() {
+                    let team = player.team();
+                    candidates.push(
+                        CompletionCandidate::new(&player.name).help(Some(team.clone().into())),
+                    );
+                    candidates.push(
+                        CompletionCandidate::new(&player.id)
+                            .help(Some(player.name.into()))
+                            .hide(true),
+                    );
+                }
+            }
+        }
+        candidates
+    }
+
+    // Completes the argument from the cache of the settings in the config
+    // files and env vars, the flags of the command line aren't parsed yet
+    pub fn completer(self) -> ArgValueCandidates {
+        ArgValueCandidates::new(move || {
+            let Ok(config) = Config::load(Settings::default()) else {
+                return Vec::new();
+            };
+            let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            else {
+                return Vec::new();
+            };
+            runtime.block_on(self.candidates(&Matchday::offline(&config), &config.season))
+        })
+    }
+}
+
+// Answers the completion requests of the scripts written by `completions`
+// and exits, before anything else is written. Does nothing otherwise.
+pub fn complete() {
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_VAR)
+        .complete();
+}
+
+// The script to source in the shell's startup file. It calls back into the
+// binary on every completion, so the cached values are always current.
+pub fn write_script(out: &mut impl Write, shell: Shell) -> anyhow::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+        Shell::PowerShell => &Powershell,
+        Shell::Elvish => &Elvish,
+        _ => anyhow::bail!("completions aren't supported for {shell}"),
+    };
+    let bin = std::env::current_exe()?;
+    let name = Cli::command().get_name().to_string();
+    completer.write_registration(COMPLETE_VAR, &name, &name, &bin.to_string_lossy(), out)?;
+    Ok(())
+}
+
+// A single man page listing every subcommand, in roff
+pub fn write_man_page(out: &mut impl Write) -> anyhow::Result<()> {
+    clap_mangen::Man::new(Cli::command()).render(out)?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "mock-server"))]
+mod test {
+    use clap_complete::CompletionCandidate;
+
+    use crate::completions::Values;
+    use crate::config::{Config, Settings, Source};
+    use crate::matchday::Matchday;
+    use crate::mock_server::{MockServer, MockServerOptions};
+
+    #[tokio::test]
+    async fn test_candidates() {
+        let server = MockServer::start(MockServerOptions::default())
+            .await
+            .unwrap();
+
+        // Only what's cached is completed
+        let config = Config::from_layers(vec![(
+            Settings {
+                api_key: Some("asdf1234".into()),
+                base_url: Some(server.url()),
+                cache_dir: Some(std::env::temp_dir().join("matchday-completions-candidates")),
+                rate_limit: Some(0.0),
+                ..Default::default()
+            },
+            Source::Default,
+        )])
+        .unwrap();
+        let offline = Matchday::offline(&config);
+        assert!(Values::Players
+            .candidates(&offline, "23/24")
+            .await
+            .is_empty());
+
+        Matchday::new(&config)
+            .unwrap()
+            .top_scorers("23/24", 1)
+            .await
+            .unwrap();
+        let requests = server.requests();
+        let values = |candidates: Vec<CompletionCandidate>| {
+            candidates
+                .iter()
+                .filter(|candidate| !candidate.is_hide_set())
+                .map(|candidate| candidate.get_value().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            values(Values::Seasons.candidates(&offline, "23/24").await),
+            ["23/24"]
+        );
+        assert_eq!(
+            values(Values::Teams.candidates(&offline, "23/24").await),
+            ["Manchester City", "Chelsea FC", "Arsenal FC"]
+        );
+        assert!(values(Values::Players.candidates(&offline, "23/24").await)
+            .contains(&"Palmer, Cole".to_string()));
+        assert_eq!(server.requests(), requests);
+
+        offline.clear_cache().unwrap();
+    }
+}
@@ -0,0 +1,620 @@
+use std::collections::HashMap;
+use std::env::{self, VarError};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::Url;
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use serde::Deserialize;
+
+use crate::api_client::{ACCESS_LEVEL, API_URL, API_VERSION, LOCALE};
+use crate::db::Database;
+use crate::error::{ConfigError, EnvVarError, Error, IOError};
+use crate::output::OutputFormat;
+use crate::secret::Secret;
+
+const CACHE_FOLDER: &str = ".matchday";
+const PROJECT_CONFIG_FILE: &str = "matchday.toml";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum AccessLevel {
+    #[default]
+    Trial,
+    Production,
+}
+
+impl AccessLevel {
+    // Path segment of the API URLs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessLevel::Trial => "trial",
+            AccessLevel::Production => "production",
+        }
+    }
+}
+
+// A layer of configuration, only the settings it has override the previous layers.
+// The same settings are read from the config files, env vars and command line.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    // Not a flag, so it doesn't end up in the shell history
+    #[cfg_attr(feature = "cli", arg(skip))]
+    pub api_key: Option<Secret>,
+    /// URL of the Sportradar API, or of a stand-in server such as matchday-mock-server
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub base_url: Option<String>,
+    /// Access level of the Sportradar API key
+    #[cfg_attr(feature = "cli", arg(long, global = true, value_enum))]
+    pub access_level: Option<AccessLevel>,
+    /// Version of the Sportradar Soccer API, such as "v4"
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub api_version: Option<String>,
+    /// Language of the player and team names, such as "en", "es" or "pt"
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub locale: Option<String>,
+    /// Season used by the commands, by ID or year such as "23/24"
+    #[cfg_attr(
+        feature = "cli",
+        arg(long, global = true, add = crate::completions::Values::Seasons.completer())
+    )]
+    pub season: Option<String>,
+    /// Folder where the fetched data is cached
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub cache_dir: Option<PathBuf>,
+    /// How long cached data is used before it's fetched again, such as "30m", "6h" or "7d"
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub cache_ttl: Option<String>,
+    /// Only reads the cache, where nothing expires, without any API call
+    #[cfg_attr(
+        feature = "cli",
+        arg(long, global = true, num_args = 0, default_missing_value = "true")
+    )]
+    pub offline: Option<bool>,
+    /// Output format of the commands
+    #[cfg_attr(feature = "cli", arg(long, global = true, value_enum))]
+    pub format: Option<OutputFormat>,
+    /// Maximum number of requests per second made to the API
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub rate_limit: Option<f64>,
+    /// Times a request that was rate limited, failed on the server, timed out or couldn't connect is retried
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub retries: Option<usize>,
+    /// Number of players printed by the top commands
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub limit: Option<usize>,
+    /// Records every API response into this file
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub record: Option<PathBuf>,
+    /// Serves the API responses recorded in this file, without any network
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub replay: Option<PathBuf>,
+    /// Writes the API and cache metrics into this file once the command finishes, in the Prometheus text format
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub metrics_file: Option<PathBuf>,
+    /// Monthly budget of API calls, such as 1000 for a trial key, warming the cache beyond it is refused
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub quota_budget: Option<u64>,
+    /// SQLite database written by `db sync` and read by `query`, in the cache dir by default
+    #[cfg_attr(feature = "cli", arg(long, global = true))]
+    pub db_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env,
+    Cli,
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub api_key: Option<Secret>,
+    pub base_url: Url,
+    pub access_level: AccessLevel,
+    pub api_version: String,
+    pub locale: String,
+    pub season: String,
+    pub cache_dir: PathBuf,
+    // Without it, only the seasons and standings expire, after a day
+    pub cache_ttl: Option<Duration>,
+    // Reads the cache without the API, see `Matchday::offline`
+    pub offline: bool,
+    pub format: OutputFormat,
+    pub rate_limit: f64,
+    pub retries: usize,
+    pub limit: usize,
+    // Cassette files, replay takes precedence if both are set
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub metrics_file: Option<PathBuf>,
+    // Calls are tracked either way, but only limited with a budget
+    pub quota_budget: Option<u64>,
+    // The cache dir's matchday.db without it
+    pub db_file: Option<PathBuf>,
+    pub(crate) sources: HashMap<&'static str, Source>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        // Ideally could use a library to run consistently on Windows. Without
+        // a home dir, the cache is relative to the working directory.
+        #[allow(deprecated)]
+        let cache_dir = std::env::home_dir().unwrap_or_default().join(CACHE_FOLDER);
+
+        Self {
+            api_key: None,
+            base_url: Url::parse(API_URL).expect("should be a valid URL"),
+            access_level: ACCESS_LEVEL,
+            api_version: API_VERSION.into(),
+            locale: LOCALE.into(),
+            season: "sr:season:105353".into(),
+            cache_dir,
+            cache_ttl: None,
+            offline: false,
+            format: OutputFormat::Table,
+            // Trial keys are limited to a request per second
+            rate_limit: 1.0,
+            retries: 3,
+            limit: 10,
+            record: None,
+            replay: None,
+            metrics_file: None,
+            quota_budget: None,
+            db_file: None,
+            sources: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    // Layers from lowest to highest priority: defaults, user config file,
+    // project config file, env vars and then command line flags
+    pub fn load(cli: Settings) -> Result<Self, Error> {
+        let mut layers = Vec::with_capacity(4);
+        for file in [Self::user_file(), Some(PathBuf::from(PROJECT_CONFIG_FILE))]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(settings) = Self::read_file(&file)? {
+                layers.push((settings, Source::File(file)));
+            }
+        }
+        layers.push((Self::read_env()?, Source::Env));
+        layers.push((cli, Source::Cli));
+
+        Self::from_layers(layers)
+    }
+
+    pub fn from_layers(layers: Vec<(Settings, Source)>) -> Result<Self, Error> {
+        let mut config = Self::default();
+        for (settings, source) in layers {
+            config.apply(settings, source)?;
+        }
+        Ok(config)
+    }
+
+    fn apply(&mut self, settings: Settings, source: Source) -> Result<(), Error> {
+        if let Some(api_key) = settings.api_key {
+            self.api_key = Some(api_key);
+            self.sources.insert("api_key", source.clone());
+        }
+        if let Some(base_url) = settings.base_url {
+            self.base_url = Url::parse(&base_url)
+                .map_err(|e| ConfigError::new(&source, "base_url", &e.to_string()))?;
+            self.sources.insert("base_url", source.clone());
+        }
+        if let Some(access_level) = settings.access_level {
+            self.access_level = access_level;
+            self.sources.insert("access_level", source.clone());
+        }
+        if let Some(api_version) = settings.api_version {
+            self.api_version = api_version;
+            self.sources.insert("api_version", source.clone());
+        }
+        if let Some(locale) = settings.locale {
+            self.locale = locale;
+            self.sources.insert("locale", source.clone());
+        }
+        if let Some(season) = settings.season {
+            self.season = season;
+            self.sources.insert("season", source.clone());
+        }
+        if let Some(cache_dir) = settings.cache_dir {
+            self.cache_dir = cache_dir;
+            self.sources.insert("cache_dir", source.clone());
+        }
+        if let Some(cache_ttl) = settings.cache_ttl {
+            self.cache_ttl = Some(
+                parse_duration(&cache_ttl)
+                    .ok_or_else(|| ConfigError::new(&source, "cache_ttl", "invalid duration"))?,
+            );
+            self.sources.insert("cache_ttl", source.clone());
+        }
+        if let Some(offline) = settings.offline {
+            self.offline = offline;
+            self.sources.insert("offline", source.clone());
+        }
+        if let Some(format) = settings.format {
+            self.format = format;
+            self.sources.insert("format", source.clone());
+        }
+        if let Some(rate_limit) = settings.rate_limit {
+            if !rate_limit.is_finite() || rate_limit < 0.0 {
+                return Err(ConfigError::new(
+                    &source,
+                    "rate_limit",
+                    "must be zero or more requests per second",
+                )
+                .into());
+            }
+            self.rate_limit = rate_limit;
+            self.sources.insert("rate_limit", source.clone());
+        }
+        if let Some(retries) = settings.retries {
+            self.retries = retries;
+            self.sources.insert("retries", source.clone());
+        }
+        if let Some(limit) = settings.limit {
+            self.limit = limit;
+            self.sources.insert("limit", source.clone());
+        }
+        if let Some(record) = settings.record {
+            self.record = Some(record);
+            self.sources.insert("record", source.clone());
+        }
+        if let Some(replay) = settings.replay {
+            self.replay = Some(replay);
+            self.sources.insert("replay", source.clone());
+        }
+        if let Some(metrics_file) = settings.metrics_file {
+            self.metrics_file = Some(metrics_file);
+            self.sources.insert("metrics_file", source.clone());
+        }
+        if let Some(quota_budget) = settings.quota_budget {
+            self.quota_budget = Some(quota_budget);
+            self.sources.insert("quota_budget", source.clone());
+        }
+        if let Some(db_file) = settings.db_file {
+            self.db_file = Some(db_file);
+            self.sources.insert("db_file", source.clone());
+        }
+        Ok(())
+    }
+
+    // Every setting with its effective value and where it came from
+    pub fn effective(&self) -> Vec<(&'static str, String, String)> {
+        let values = [
+            (
+                "api_key",
+                match self.api_key {
+                    Some(_) => "********".to_string(),
+                    None => "(not set)".to_string(),
+                },
+            ),
+            ("base_url", self.base_url.to_string()),
+            ("access_level", self.access_level.as_str().to_string()),
+            ("api_version", self.api_version.clone()),
+            ("locale", self.locale.clone()),
+            ("season", self.season.clone()),
+            ("cache_dir", self.cache_dir.display().to_string()),
+            (
+                "cache_ttl",
+                match self.cache_ttl {
+                    Some(ttl) => format!("{}s", ttl.as_secs()),
+                    None => "(never expires)".to_string(),
+                },
+            ),
+            ("offline", self.offline.to_string()),
+            ("format", self.format.as_str().to_string()),
+            ("rate_limit", self.rate_limit.to_string()),
+            ("retries", self.retries.to_string()),
+            ("limit", self.limit.to_string()),
+            ("record", display_path(&self.record)),
+            ("replay", display_path(&self.replay)),
+            ("metrics_file", display_path(&self.metrics_file)),
+            (
+                "quota_budget",
+                match self.quota_budget {
+                    Some(budget) => budget.to_string(),
+                    None => "(unlimited)".to_string(),
+                },
+            ),
+            ("db_file", Database::file(self).display().to_string()),
+        ];
+
+        values
+            .into_iter()
+            .map(|(key, value)| {
+                let source = match self.sources.get(key).unwrap_or(&Source::Default) {
+                    Source::Default => "default".to_string(),
+                    Source::File(file) => file.display().to_string(),
+                    Source::Env => format!("env {}", env_var(key)),
+                    Source::Cli => "command line".to_string(),
+                };
+                (key, value, source)
+            })
+            .collect()
+    }
+
+    fn user_file() -> Option<PathBuf> {
+        let mut file = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                #[allow(deprecated)]
+                let mut dir = std::env::home_dir()?;
+                dir.push(".config");
+                dir
+            }
+        };
+        file.push("matchday");
+        file.push("config.toml");
+        Some(file)
+    }
+
+    fn read_file(file: &Path) -> Result<Option<Settings>, Error> {
+        if !file.exists() {
+            return Ok(None);
+        }
+        let raw =
+            fs::read_to_string(file).map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+        // Only the message, the full error quotes the offending line, which may hold the API key
+        let settings = toml::from_str(&raw)
+            .map_err(|e| ConfigError::new(&Source::File(file.to_path_buf()), "", e.message()))?;
+        Ok(Some(settings))
+    }
+
+    fn read_env() -> Result<Settings, Error> {
+        fn var(key: &str) -> Result<Option<String>, Error> {
+            match env::var(env_var(key)) {
+                Ok(value) => Ok(Some(value)),
+                Err(VarError::NotPresent) => Ok(None),
+                Err(e) => Err(EnvVarError::new(env_var(key), e).into()),
+            }
+        }
+        fn parse<T: std::str::FromStr>(key: &str) -> Result<Option<T>, Error> {
+            var(key)?
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| ConfigError::new(&Source::Env, key, "invalid value").into())
+                })
+                .transpose()
+        }
+        // Same names as in the config files, ignoring case
+        fn parse_enum<T: DeserializeOwned>(key: &str) -> Result<Option<T>, Error> {
+            var(key)?
+                .map(|value| {
+                    T::deserialize(value.to_lowercase().into_deserializer()).map_err(
+                        |e: serde::de::value::Error| {
+                            ConfigError::new(&Source::Env, key, &e.to_string()).into()
+                        },
+                    )
+                })
+                .transpose()
+        }
+
+        Ok(Settings {
+            api_key: var("api_key")?.map(Secret::from),
+            base_url: var("base_url")?,
+            access_level: parse_enum("access_level")?,
+            api_version: var("api_version")?,
+            locale: var("locale")?,
+            season: var("season")?,
+            cache_dir: var("cache_dir")?.map(PathBuf::from),
+            cache_ttl: var("cache_ttl")?,
+            offline: parse("offline")?,
+            format: parse_enum("format")?,
+            rate_limit: parse("rate_limit")?,
+            retries: parse("retries")?,
+            limit: parse("limit")?,
+            record: var("record")?.map(PathBuf::from),
+            replay: var("replay")?.map(PathBuf::from),
+            metrics_file: var("metrics_file")?.map(PathBuf::from),
+            quota_budget: parse("quota_budget")?,
+            db_file: var("db_file")?.map(PathBuf::from),
+        })
+    }
+}
+
+fn env_var(key: &str) -> &'static str {
+    match key {
+        "api_key" => "SPORTRADAR_API_KEY",
+        "base_url" => "MATCHDAY_BASE_URL",
+        "access_level" => "MATCHDAY_ACCESS_LEVEL",
+        "api_version" => "MATCHDAY_API_VERSION",
+        "locale" => "MATCHDAY_LOCALE",
+        "season" => "MATCHDAY_SEASON",
+        "cache_dir" => "MATCHDAY_CACHE_DIR",
+        "cache_ttl" => "MATCHDAY_CACHE_TTL",
+        "offline" => "MATCHDAY_OFFLINE",
+        "format" => "MATCHDAY_FORMAT",
+        "rate_limit" => "MATCHDAY_RATE_LIMIT",
+        "retries" => "MATCHDAY_RETRIES",
+        "limit" => "MATCHDAY_LIMIT",
+        "record" => "MATCHDAY_RECORD",
+        "replay" => "MATCHDAY_REPLAY",
+        "metrics_file" => "MATCHDAY_METRICS_FILE",
+        "quota_budget" => "MATCHDAY_QUOTA_BUDGET",
+        "db_file" => "MATCHDAY_DB_FILE",
+        _ => unreachable!("unknown setting {key}"),
+    }
+}
+
+fn display_path(path: &Option<PathBuf>) -> String {
+    match path {
+        Some(path) => path.display().to_string(),
+        None => "(off)".to_string(),
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "defaults"),
+            Source::File(file) => write!(f, "{}", file.display()),
+            Source::Env => write!(f, "env vars"),
+            Source::Cli => write!(f, "command line"),
+        }
+    }
+}
+
+// Durations such as "90s", "30m", "6h" or "7d", plain numbers are seconds
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let number: u64 = number.parse().ok()?;
+    let seconds = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(number.checked_mul(seconds)?))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use crate::config::{parse_duration, AccessLevel, Config, Settings, Source};
+    use crate::output::OutputFormat;
+    use crate::secret::Secret;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("60s"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration("30m"), Some(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("6h"), Some(Duration::from_secs(6 * 60 * 60)));
+        assert_eq!(
+            parse_duration("7d"),
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(parse_duration("7w"), None);
+        assert_eq!(parse_duration("h"), None);
+        // overflows
+        assert_eq!(parse_duration(&format!("{}d", u64::MAX)), None);
+    }
+
+    #[test]
+    fn test_layers() {
+        let user_file: Settings = toml::from_str(
+            r#"
+                api_key = "asdf1234"
+                access_level = "production"
+                locale = "es"
+                cache_ttl = "6h"
+            "#,
+        )
+        .unwrap();
+        let project_file: Settings = toml::from_str(
+            r#"
+                locale = "pt"
+                season = "22/23"
+            "#,
+        )
+        .unwrap();
+        let env = Settings {
+            season: Some("21/22".into()),
+            ..Default::default()
+        };
+        let cli = Settings {
+            format: Some(OutputFormat::Json),
+            ..Default::default()
+        };
+
+        let config = Config::from_layers(vec![
+            (user_file, Source::File(PathBuf::from("config.toml"))),
+            (project_file, Source::File(PathBuf::from("matchday.toml"))),
+            (env, Source::Env),
+            (cli, Source::Cli),
+        ])
+        .unwrap();
+
+        assert_eq!(config.api_key, Some(Secret::new("asdf1234")));
+        assert_eq!(config.access_level, AccessLevel::Production);
+        assert_eq!(config.locale, "pt");
+        assert_eq!(config.season, "21/22");
+        assert_eq!(config.cache_ttl, Some(Duration::from_secs(6 * 60 * 60)));
+        assert_eq!(config.format, OutputFormat::Json);
+        assert_eq!(config.limit, 10);
+
+        let sources: Vec<(&str, String)> = config
+            .effective()
+            .into_iter()
+            .map(|(key, _, source)| (key, source))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("api_key", "config.toml".into()),
+                ("base_url", "default".into()),
+                ("access_level", "config.toml".into()),
+                ("api_version", "default".into()),
+                ("locale", "matchday.toml".into()),
+                ("season", "env MATCHDAY_SEASON".into()),
+                ("cache_dir", "default".into()),
+                ("cache_ttl", "config.toml".into()),
+                ("offline", "default".into()),
+                ("format", "command line".into()),
+                ("rate_limit", "default".into()),
+                ("retries", "default".into()),
+                ("limit", "default".into()),
+                ("record", "default".into()),
+                ("replay", "default".into()),
+                ("metrics_file", "default".into()),
+                ("quota_budget", "default".into()),
+                ("db_file", "default".into()),
+            ]
+        );
+        // the key itself is never shown
+        assert_eq!(config.effective()[0].1, "********");
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        assert!(toml::from_str::<Settings>("unknown = 1").is_err());
+        assert!(toml::from_str::<Settings>("access_level = \"gold\"").is_err());
+
+        let ttl = Settings {
+            cache_ttl: Some("soon".into()),
+            ..Default::default()
+        };
+        let err = Config::from_layers(vec![(ttl, Source::Cli)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid configuration: invalid duration (cache_ttl from command line)"
+        );
+
+        for rate_limit in [-1.0, f64::NAN, f64::INFINITY] {
+            let rate_limit = Settings {
+                rate_limit: Some(rate_limit),
+                ..Default::default()
+            };
+            let err = Config::from_layers(vec![(rate_limit, Source::Env)]).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "invalid configuration: must be zero or more requests per second (rate_limit from env vars)"
+            );
+        }
+
+        // The offending line isn't quoted, as it may hold the API key
+        let file = std::env::temp_dir().join("matchday-invalid-config.toml");
+        std::fs::write(&file, "api_key = \"asdf1234").unwrap();
+        let err = Config::read_file(&file).unwrap_err();
+        let _ = std::fs::remove_file(&file);
+        assert!(!format!("{err} {err:?}").contains("asdf1234"));
+    }
+}
@@ -0,0 +1,352 @@
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, OpenFlags};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::error::{Error, IOError};
+use crate::output::Table;
+use crate::types::{Competitor, Season};
+
+// Kept by clearing the cache, it may have seasons that aren't cached anymore
+pub const DATABASE_FILE: &str = "matchday.db";
+
+// Players who moved mid season have a row for each club they played for
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS seasons (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    year TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS competitors (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    abbreviation TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS players (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS competitor_season_stats (
+    season_id TEXT NOT NULL REFERENCES seasons (id),
+    competitor_id TEXT NOT NULL REFERENCES competitors (id),
+    matches_played INTEGER NOT NULL,
+    average_ball_possession REAL NOT NULL,
+    goals_scored INTEGER NOT NULL,
+    goals_conceded INTEGER NOT NULL,
+    shots_total INTEGER NOT NULL,
+    shots_on_target INTEGER NOT NULL,
+    corner_kicks INTEGER NOT NULL,
+    yellow_cards INTEGER NOT NULL,
+    red_cards INTEGER NOT NULL,
+    PRIMARY KEY (season_id, competitor_id)
+);
+CREATE TABLE IF NOT EXISTS player_season_stats (
+    season_id TEXT NOT NULL REFERENCES seasons (id),
+    player_id TEXT NOT NULL REFERENCES players (id),
+    competitor_id TEXT NOT NULL REFERENCES competitors (id),
+    matches_played INTEGER NOT NULL,
+    goals_scored INTEGER NOT NULL,
+    assists INTEGER NOT NULL,
+    goals_by_head INTEGER NOT NULL,
+    goals_by_penalty INTEGER NOT NULL,
+    penalties_missed INTEGER NOT NULL,
+    own_goals INTEGER NOT NULL,
+    shots_on_target INTEGER NOT NULL,
+    shots_off_target INTEGER NOT NULL,
+    shots_blocked INTEGER NOT NULL,
+    corner_kicks INTEGER NOT NULL,
+    offsides INTEGER NOT NULL,
+    yellow_cards INTEGER NOT NULL,
+    yellow_red_cards INTEGER NOT NULL,
+    red_cards INTEGER NOT NULL,
+    substituted_in INTEGER NOT NULL,
+    substituted_out INTEGER NOT NULL,
+    PRIMARY KEY (season_id, player_id, competitor_id)
+);
+";
+
+// A SQLite database of the data fetched through `Client`, normalized so it
+// can be queried with plain SQL, such as joining the stats across seasons
+pub struct Database {
+    conn: Connection,
+}
+
+// What a sync wrote
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub seasons: usize,
+    pub competitors: usize,
+    pub players: usize,
+}
+
+impl Database {
+    // Creates the file and the tables if they don't exist yet
+    pub fn open(file: &Path) -> Result<Self, Error> {
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|io_err| IOError::new(dir.to_path_buf(), io_err))?;
+        }
+        let conn = Connection::open(file)?;
+        conn.execute_batch(SCHEMA)?;
+        // Databases synced before the minutes were dropped had them all as 0
+        let has_minutes: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('player_season_stats')
+             WHERE name = 'minutes_played'",
+            [],
+            |row| row.get(0),
+        )?;
+        if has_minutes {
+            conn.execute(
+                "ALTER TABLE player_season_stats DROP COLUMN minutes_played",
+                [],
+            )?;
+        }
+        Ok(Self { conn })
+    }
+
+    // So queries can't change what was synced
+    pub fn open_read_only(file: &Path) -> Result<Self, Error> {
+        if !file.exists() {
+            return Err(Error::NoDatabase(file.to_path_buf()));
+        }
+        let conn = Connection::open_with_flags(file, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Ok(Self { conn })
+    }
+
+    // The configured file, or the one in the cache dir
+    pub fn file(config: &Config) -> PathBuf {
+        config
+            .db_file
+            .clone()
+            .unwrap_or_else(|| config.cache_dir.join(DATABASE_FILE))
+    }
+
+    // Every season of the competition, and the teams of the synced seasons
+    // with their players. The stats of a synced season replace the ones
+    // stored before, in a single transaction.
+    pub fn sync(
+        &mut self,
+        seasons: &[Season],
+        teams: &[(String, Vec<Competitor>)],
+    ) -> Result<SyncSummary, Error> {
+        let tx = self.conn.transaction()?;
+        let mut summary = SyncSummary::default();
+
+        for season in seasons {
+            tx.execute(
+                "INSERT INTO seasons (id, name, year, start_date, end_date)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (id) DO UPDATE SET
+                     name = excluded.name,
+                     year = excluded.year,
+                     start_date = excluded.start_date,
+                     end_date = excluded.end_date",
+                params![
+                    season.id,
+                    season.name,
+                    season.year,
+                    season.start_date.to_string(),
+                    season.end_date.to_string()
+                ],
+            )?;
+            summary.seasons += 1;
+        }
+
+        for (season, competitors) in teams {
+            tx.execute(
+                "DELETE FROM competitor_season_stats WHERE season_id = ?1",
+                [season],
+            )?;
+            tx.execute(
+                "DELETE FROM player_season_stats WHERE season_id = ?1",
+                [season],
+            )?;
+
+            for competitor in competitors {
+                tx.execute(
+                    "INSERT INTO competitors (id, name, abbreviation) VALUES (?1, ?2, ?3)
+                     ON CONFLICT (id) DO UPDATE SET
+                         name = excluded.name,
+                         abbreviation = excluded.abbreviation",
+                    params![competitor.id, competitor.name, competitor.abbreviation],
+                )?;
+                let stats = &competitor.statistics;
+                tx.execute(
+                    "INSERT INTO competitor_season_stats VALUES
+                     (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        season,
+                        competitor.id,
+                        stats.matches_played,
+                        stats.average_ball_possession,
+                        stats.goals_scored,
+                        stats.goals_conceded,
+                        stats.shots_total,
+                        stats.shots_on_target,
+                        stats.corner_kicks,
+                        stats.yellow_cards,
+                        stats.red_cards
+                    ],
+                )?;
+                summary.competitors += 1;
+
+                for player in &competitor.players {
+                    tx.execute(
+                        "INSERT INTO players (id, name) VALUES (?1, ?2)
+                         ON CONFLICT (id) DO UPDATE SET name = excluded.name",
+                        params![player.id, player.name],
+                    )?;
+                    let stats = &player.statistics;
+                    tx.execute(
+                        "INSERT INTO player_season_stats VALUES
+                         (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                          ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+                        params![
+                            season,
+                            player.id,
+                            competitor.id,
+                            stats.matches_played,
+                            stats.goals_scored,
+                            stats.assists,
+                            stats.goals_by_head,
+                            stats.goals_by_penalty,
+                            stats.penalties_missed,
+                            stats.own_goals,
+                            stats.shots_on_target,
+                            stats.shots_off_target,
+                            stats.shots_blocked,
+                            stats.corner_kicks,
+                            stats.offsides,
+                            stats.yellow_cards,
+                            stats.yellow_red_cards,
+                            stats.red_cards,
+                            stats.substituted_in,
+                            stats.substituted_out
+                        ],
+                    )?;
+                    summary.players += 1;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    // The columns of the result become the headers, in the order selected
+    pub fn query(&self, sql: &str) -> Result<Table, Error> {
+        let mut statement = self.conn.prepare(sql)?;
+        let headers: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        let mut table = Table::new(&headers);
+
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let values = (0..headers.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => Value::Null,
+                        ValueRef::Integer(value) => json!(value),
+                        ValueRef::Real(value) => json!(value),
+                        ValueRef::Text(text) => json!(String::from_utf8_lossy(text)),
+                        ValueRef::Blob(blob) => json!(format!("<{} bytes>", blob.len())),
+                    })
+                })
+                .collect::<Result<Vec<Value>, Error>>()?;
+            table.push(values);
+        }
+
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use serde_json::json;
+
+    use crate::db::Database;
+    use crate::error::Error;
+    use crate::test_support::{mock_season, mock_team};
+
+    #[test]
+    fn test_sync_and_query() {
+        let file = std::env::temp_dir().join("matchday-db-sync.db");
+        let _ = fs::remove_file(&file);
+
+        let seasons = [
+            mock_season("sr:season:1", "22/23"),
+            mock_season("sr:season:2", "23/24"),
+        ];
+        let mut db = Database::open(&file).unwrap();
+        let summary = db
+            .sync(
+                &seasons,
+                &[
+                    (
+                        "sr:season:1".into(),
+                        vec![mock_team("bha", &[("p1", "Mitoma, Kaoru", 7)])],
+                    ),
+                    (
+                        "sr:season:2".into(),
+                        vec![
+                            mock_team("bha", &[("p1", "Mitoma, Kaoru", 3)]),
+                            mock_team("che", &[("p2", "Palmer, Cole", 22)]),
+                        ],
+                    ),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            (summary.seasons, summary.competitors, summary.players),
+            (2, 3, 3)
+        );
+
+        // synced again, the stats of the season are replaced
+        db.sync(
+            &seasons,
+            &[(
+                "sr:season:2".into(),
+                vec![mock_team("che", &[("p2", "Palmer, Cole", 22)])],
+            )],
+        )
+        .unwrap();
+
+        let db = Database::open_read_only(&file).unwrap();
+        let table = db
+            .query(
+                "SELECT p.name, s.year, SUM(ps.goals_scored) AS goals
+                 FROM player_season_stats ps
+                 JOIN players p ON p.id = ps.player_id
+                 JOIN seasons s ON s.id = ps.season_id
+                 GROUP BY p.name, s.year
+                 ORDER BY s.year, goals DESC",
+            )
+            .unwrap();
+        assert_eq!(table.headers, ["name", "year", "goals"]);
+        assert_eq!(
+            table.rows,
+            [
+                vec![json!("Mitoma, Kaoru"), json!("22/23"), json!(7)],
+                vec![json!("Palmer, Cole"), json!("23/24"), json!(22)],
+            ]
+        );
+
+        assert!(db.query("DELETE FROM players").is_err());
+        let _ = fs::remove_file(&file);
+
+        assert!(matches!(
+            Database::open_read_only(&file),
+            Err(Error::NoDatabase(_))
+        ));
+    }
+}
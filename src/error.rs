@@ -11,6 +11,73 @@ pub enum Error {
 
     #[error("JSON serialization error: {0}")]
     JSON(#[from] JSONError),
+
+    #[error("invalid configuration: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("missing Sportradar API key, set SPORTRADAR_API_KEY or api_key in the config file")]
+    MissingApiKey,
+
+    #[error("invalid Sportradar API key, it can only have visible ASCII characters")]
+    InvalidApiKey,
+
+    #[error("invalid rate limit {0}, it must be zero or more requests per second")]
+    InvalidRateLimit(f64),
+
+    #[error("no recorded response for the {0}")]
+    NotRecorded(String),
+
+    #[error("invalid HTTP status {0} in the recorded response")]
+    InvalidStatus(u16),
+
+    #[error("no cached {0}, fetch them without --offline first")]
+    NotCached(String),
+
+    #[error("no season matches \"{0}\"")]
+    SeasonNotFound(String),
+
+    #[error("no player matches \"{0}\"")]
+    PlayerNotFound(String),
+
+    #[error("\"{query}\" matches more than one player: {}", matches.join(" / "))]
+    AmbiguousPlayer { query: String, matches: Vec<String> },
+
+    #[error("no team matches \"{0}\"")]
+    TeamNotFound(String),
+
+    #[error("needs {needed} API calls, but only {remaining} are left in this month's budget")]
+    QuotaExceeded { needed: u64, remaining: u64 },
+
+    #[error("no snapshot named \"{0}\"")]
+    SnapshotNotFound(String),
+
+    #[error("a snapshot named \"{0}\" already exists")]
+    SnapshotExists(String),
+
+    #[error("snapshots \"{from}\" and \"{to}\" are of different seasons, {from_season} and {to_season}")]
+    SnapshotSeasonMismatch {
+        from: String,
+        to: String,
+        from_season: String,
+        to_season: String,
+    },
+
+    #[error("invalid snapshot name \"{0}\", it can only have letters, digits, '-', '_' and '.'")]
+    InvalidSnapshotName(String),
+
+    #[error("database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("no database at {}, create it with `matchday db sync`", .0.display())]
+    NoDatabase(std::path::PathBuf),
+
+    #[cfg(feature = "export")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[cfg(feature = "export")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -47,6 +114,25 @@ impl EnvVarError {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("{message} ({origin})")]
+pub struct ConfigError {
+    origin: String,
+    message: String,
+}
+
+impl ConfigError {
+    pub fn new(source: &crate::config::Source, key: &str, message: &str) -> Self {
+        Self {
+            origin: match key {
+                "" => source.to_string(),
+                key => format!("{key} from {source}"),
+            },
+            message: message.into(),
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("{source} {}", match file { Some(f) => f.clone().into_os_string().into_string().unwrap(), None => "".into()})]
 pub struct JSONError {
@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{Error, IOError, JSONError};
+use crate::history::SeasonPlayers;
+use crate::types::PlayerStats;
+
+// Files pandas and polars load directly
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+    // Arrow IPC file, also known as Feather v2
+    Arrow,
+}
+
+impl ExportFormat {
+    // By the extension of the file
+    pub fn from_file(file: &Path) -> Option<Self> {
+        match file.extension()?.to_str()? {
+            "parquet" => Some(ExportFormat::Parquet),
+            "arrow" | "feather" | "ipc" => Some(ExportFormat::Arrow),
+            _ => None,
+        }
+    }
+}
+
+// A row for each player in each season, once for each club a player who moved
+// mid season played for, with a column for each stat
+#[derive(Serialize)]
+struct Row<'a> {
+    season_id: &'a str,
+    season: &'a str,
+    player_id: &'a str,
+    player_name: &'a str,
+    team: &'a str,
+    #[serde(flatten)]
+    statistics: &'a PlayerStats,
+}
+
+const COLUMNS: [&str; 5] = ["season_id", "season", "player_id", "player_name", "team"];
+
+// The stat columns come from serializing `PlayerStats`, so a stat added to it
+// is exported without changing this, ordered by name
+pub fn schema() -> Result<SchemaRef, Error> {
+    let mut fields: Vec<Field> = COLUMNS
+        .iter()
+        .map(|name| Field::new(*name, DataType::Utf8, false))
+        .collect();
+
+    let stats =
+        serde_json::to_value(PlayerStats::default()).map_err(|e| JSONError::new(None, e))?;
+    let Value::Object(stats) = stats else {
+        unreachable!("player stats are serialized as an object")
+    };
+    fields.extend(stats.iter().map(|(name, value)| {
+        let data_type = match value {
+            Value::Number(n) if n.is_u64() => DataType::UInt64,
+            Value::Number(_) => DataType::Float64,
+            Value::Bool(_) => DataType::Boolean,
+            _ => DataType::Utf8,
+        };
+        Field::new(name, data_type, false)
+    }));
+
+    Ok(Arc::new(Schema::new(fields)))
+}
+
+pub fn record_batch(seasons: &[SeasonPlayers]) -> Result<RecordBatch, Error> {
+    let schema = schema()?;
+    let rows: Vec<Row> = seasons
+        .iter()
+        .flat_map(|season| {
+            season.players.iter().map(|player| Row {
+                season_id: &season.season.id,
+                season: &season.season.year,
+                player_id: &player.id,
+                player_name: &player.name,
+                // A single club, as the players are split by team
+                team: player.teams.first().map(String::as_str).unwrap_or_default(),
+                statistics: &player.statistics,
+            })
+        })
+        .collect();
+
+    let mut decoder = ReaderBuilder::new(schema.clone()).build_decoder()?;
+    decoder.serialize(&rows)?;
+    Ok(decoder
+        .flush()?
+        .unwrap_or_else(|| RecordBatch::new_empty(schema)))
+}
+
+// Parquet files are compressed with Snappy, which every reader supports
+pub fn write(file: &Path, format: ExportFormat, batch: &RecordBatch) -> Result<(), Error> {
+    let out = File::create(file).map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+
+    match format {
+        ExportFormat::Parquet => {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let mut writer = ArrowWriter::try_new(out, batch.schema(), Some(properties))?;
+            writer.write(batch)?;
+            writer.close()?;
+        }
+        ExportFormat::Arrow => {
+            let mut writer = arrow_ipc::writer::FileWriter::try_new(out, &batch.schema())?;
+            writer.write(batch)?;
+            writer.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{self, File};
+
+    use arrow_array::{Array, StringArray, UInt64Array};
+    use arrow_schema::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use std::path::Path;
+
+    use crate::export::{record_batch, schema, write, ExportFormat};
+    use crate::test_support::mock_season_players;
+
+    #[test]
+    fn test_schema() {
+        let schema = schema().unwrap();
+        // the fixed columns and the 17 stats
+        assert_eq!(schema.fields().len(), 5 + 17);
+        assert_eq!(schema.field(2).name(), "player_id");
+        assert_eq!(
+            schema.field_with_name("goals_scored").unwrap().data_type(),
+            &DataType::UInt64
+        );
+    }
+
+    #[test]
+    fn test_from_file() {
+        assert_eq!(
+            ExportFormat::from_file(Path::new("players.parquet")),
+            Some(ExportFormat::Parquet)
+        );
+        assert_eq!(
+            ExportFormat::from_file(Path::new("players.feather")),
+            Some(ExportFormat::Arrow)
+        );
+        assert_eq!(ExportFormat::from_file(Path::new("players.csv")), None);
+    }
+
+    #[test]
+    fn test_write() {
+        let seasons = [
+            mock_season_players("sr:season:1", "22/23", &[("BHA", "1", 7)]),
+            mock_season_players("sr:season:2", "23/24", &[("CHE", "2", 22), ("BHA", "1", 3)]),
+        ];
+        let batch = record_batch(&seasons).unwrap();
+        assert_eq!(batch.num_rows(), 3);
+
+        let file = std::env::temp_dir().join("matchday-export.parquet");
+        write(&file, ExportFormat::Parquet, &batch).unwrap();
+        let read = ParquetRecordBatchReaderBuilder::try_new(File::open(&file).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let _ = fs::remove_file(&file);
+        assert_eq!(read, batch);
+
+        let column = |name: &str| read.column(read.schema().index_of(name).unwrap()).clone();
+        let seasons = column("season");
+        let seasons = seasons.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(
+            seasons.iter().flatten().collect::<Vec<_>>(),
+            ["22/23", "23/24", "23/24"]
+        );
+        let goals = column("goals_scored");
+        let goals = goals.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(goals.values(), &[7, 22, 3]);
+
+        let file = std::env::temp_dir().join("matchday-export.arrow");
+        write(&file, ExportFormat::Arrow, &batch).unwrap();
+        let mut reader =
+            arrow_ipc::reader::FileReader::try_new(File::open(&file).unwrap(), None).unwrap();
+        let _ = fs::remove_file(&file);
+        assert_eq!(reader.next().unwrap().unwrap(), batch);
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+
+use crate::types::{MatchCompetitor, MatchStatus, MatchSummary, Qualifier};
+
+impl MatchStatus {
+    // Closed matches have confirmed results, so they never change again
+    pub fn is_finished(&self) -> bool {
+        *self == MatchStatus::Closed
+    }
+}
+
+// Time after kick-off in which the stats of the teams may still change, the
+// match itself and a while for the stats to be updated once it's over
+const IN_PLAY_HOURS: i64 = 3;
+
+impl MatchSummary {
+    // Live, or kicked off recently enough to be live or just finished
+    pub fn is_in_play(&self, now: DateTime<Utc>) -> bool {
+        let since_kick_off = now - self.sport_event.start_time;
+        self.sport_event_status.status == MatchStatus::Live
+            || (since_kick_off >= TimeDelta::zero()
+                && since_kick_off <= TimeDelta::hours(IN_PLAY_HOURS))
+    }
+    pub fn competitor(&self, qualifier: Qualifier) -> Option<&MatchCompetitor> {
+        self.sport_event
+            .competitors
+            .iter()
+            .find(|c| c.qualifier == Some(qualifier))
+    }
+    pub fn round(&self) -> Option<usize> {
+        self.sport_event
+            .sport_event_context
+            .round
+            .as_ref()
+            .and_then(|round| round.number)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FixtureFilter {
+    // Matched against the name or abbreviation, ignoring case
+    pub team: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub round: Option<usize>,
+    pub status: Option<MatchStatus>,
+}
+
+impl FixtureFilter {
+    fn matches(&self, summary: &MatchSummary) -> bool {
+        let date = summary.sport_event.start_time.date_naive();
+
+        let team = self.team.as_ref().is_none_or(|team| {
+            let team = team.to_lowercase();
+            summary.sport_event.competitors.iter().any(|c| {
+                c.name.to_lowercase().contains(&team) || c.abbreviation.to_lowercase() == team
+            })
+        });
+        let from = self.from.is_none_or(|from| date >= from);
+        let to = self.to.is_none_or(|to| date <= to);
+        let round = self
+            .round
+            .is_none_or(|round| summary.round() == Some(round));
+        let status = self
+            .status
+            .is_none_or(|status| summary.sport_event_status.status == status);
+
+        team && from && to && round && status
+    }
+}
+
+pub struct Fixtures(Vec<MatchSummary>);
+
+impl Fixtures {
+    pub fn new(schedules: Vec<MatchSummary>) -> Self {
+        Self(schedules)
+    }
+    // IDs of the teams with a match in play
+    pub fn teams_in_play(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut teams: Vec<String> = Vec::new();
+        for summary in self.0.iter().filter(|summary| summary.is_in_play(now)) {
+            for competitor in &summary.sport_event.competitors {
+                if !teams.contains(&competitor.id) {
+                    teams.push(competitor.id.clone());
+                }
+            }
+        }
+        teams
+    }
+    pub fn filter(mut self, filter: &FixtureFilter) -> impl Iterator<Item = MatchSummary> {
+        self.0.retain(|summary| filter.matches(summary));
+        self.0.sort_by_key(|summary| summary.sport_event.start_time);
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::fixtures::{FixtureFilter, Fixtures};
+    use crate::types::{
+        MatchCompetitor, MatchStatus, MatchSummary, Qualifier, Round, SportEvent,
+        SportEventContext, SportEventStatus,
+    };
+
+    fn mock_match(id: &str, start_time: &str, round: usize, teams: (&str, &str)) -> MatchSummary {
+        let competitor = |abbreviation: &str, qualifier| MatchCompetitor {
+            id: abbreviation.to_string(),
+            name: Default::default(),
+            abbreviation: abbreviation.to_string(),
+            qualifier: Some(qualifier),
+        };
+        MatchSummary {
+            sport_event: SportEvent {
+                id: id.to_string(),
+                start_time: start_time.parse().unwrap(),
+                sport_event_context: SportEventContext {
+                    round: Some(Round {
+                        number: Some(round),
+                    }),
+                },
+                competitors: vec![
+                    competitor(teams.0, Qualifier::Home),
+                    competitor(teams.1, Qualifier::Away),
+                ],
+            },
+            sport_event_status: SportEventStatus {
+                status: MatchStatus::Closed,
+                home_score: Some(0),
+                away_score: Some(0),
+            },
+        }
+    }
+
+    fn mock_fixtures() -> Fixtures {
+        Fixtures::new(vec![
+            mock_match("3", "2023-08-19T14:00:00Z", 2, ("MCI", "NEW")),
+            mock_match("1", "2023-08-11T19:00:00Z", 1, ("BUR", "MCI")),
+            mock_match("2", "2023-08-12T12:00:00Z", 1, ("ARS", "NFO")),
+        ])
+    }
+
+    fn ids(fixtures: impl Iterator<Item = MatchSummary>) -> Vec<String> {
+        fixtures.map(|m| m.sport_event.id).collect()
+    }
+
+    #[test]
+    fn test_no_filter_sorts_by_kickoff() {
+        assert_eq!(
+            ids(mock_fixtures().filter(&FixtureFilter::default())),
+            vec!["1", "2", "3"]
+        );
+    }
+
+    #[test]
+    fn test_filter_by_team_and_round() {
+        let filter = FixtureFilter {
+            team: Some("mci".into()),
+            ..Default::default()
+        };
+        assert_eq!(ids(mock_fixtures().filter(&filter)), vec!["1", "3"]);
+
+        let filter = FixtureFilter {
+            team: Some("mci".into()),
+            round: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(ids(mock_fixtures().filter(&filter)), vec!["3"]);
+    }
+
+    #[test]
+    fn test_filter_by_date_range() {
+        let filter = FixtureFilter {
+            from: NaiveDate::from_ymd_opt(2023, 8, 12),
+            to: NaiveDate::from_ymd_opt(2023, 8, 19),
+            ..Default::default()
+        };
+        assert_eq!(ids(mock_fixtures().filter(&filter)), vec!["2", "3"]);
+
+        let filter = FixtureFilter {
+            status: Some(MatchStatus::NotStarted),
+            ..Default::default()
+        };
+        assert_eq!(mock_fixtures().filter(&filter).count(), 0);
+    }
+
+    #[test]
+    fn test_teams_in_play() {
+        let fixtures = mock_fixtures();
+        let at = |time: &str| time.parse().unwrap();
+        // before kick-off
+        assert!(fixtures
+            .teams_in_play(at("2023-08-19T13:59:00Z"))
+            .is_empty());
+        assert_eq!(
+            fixtures.teams_in_play(at("2023-08-19T15:30:00Z")),
+            ["MCI", "NEW"]
+        );
+        assert!(fixtures
+            .teams_in_play(at("2023-08-19T17:01:00Z"))
+            .is_empty());
+
+        let mut live = mock_match("4", "2023-08-12T12:00:00Z", 1, ("CHE", "LIV"));
+        live.sport_event_status.status = MatchStatus::Live;
+        assert_eq!(
+            Fixtures::new(vec![live]).teams_in_play(at("2023-08-19T15:30:00Z")),
+            ["CHE", "LIV"]
+        );
+    }
+}
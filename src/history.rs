@@ -0,0 +1,105 @@
+use crate::players::SeasonPlayer;
+use crate::types::{PlayerStats, Season};
+
+// Every player of a season, split by the team they played for
+pub struct SeasonPlayers {
+    pub season: Season,
+    pub players: Vec<SeasonPlayer>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub season: String,
+    pub team: String,
+    pub statistics: PlayerStats,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerHistory {
+    pub id: String,
+    pub name: String,
+    // One entry per season and club, a transfer mid season gives two of them
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl PlayerHistory {
+    // Players are identified across seasons by their Sportradar ID,
+    // the latest name is kept as it may be spelled differently over time
+    pub fn new(id: &str, seasons: &[SeasonPlayers]) -> Self {
+        let mut name = String::new();
+        let mut entries = Vec::new();
+
+        for season in seasons {
+            for player in season.players.iter().filter(|p| p.id == id) {
+                name = player.name.clone();
+                entries.push(HistoryEntry {
+                    season: season.season.year.clone(),
+                    team: player.team(),
+                    statistics: player.statistics.clone(),
+                });
+            }
+        }
+
+        Self {
+            id: id.to_string(),
+            name,
+            entries,
+        }
+    }
+
+    pub fn total(&self) -> PlayerStats {
+        let mut total = PlayerStats::default();
+        for entry in &self.entries {
+            total += &entry.statistics;
+        }
+        total
+    }
+
+    pub fn teams(&self) -> Vec<String> {
+        let mut teams: Vec<String> = Vec::new();
+        for entry in &self.entries {
+            if !teams.contains(&entry.team) {
+                teams.push(entry.team.clone());
+            }
+        }
+        teams
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::history::PlayerHistory;
+    use crate::test_support::mock_season_players;
+
+    #[test]
+    fn test_history_across_clubs() {
+        let seasons = vec![
+            mock_season_players("sr:season:1", "21/22", &[("CHE", "1", 3), ("ARS", "2", 10)]),
+            mock_season_players("sr:season:2", "22/23", &[("CHE", "1", 1), ("MCI", "1", 2)]),
+            mock_season_players("sr:season:3", "23/24", &[("MCI", "2", 5), ("CHE", "1", 22)]),
+        ];
+
+        let history = PlayerHistory::new("1", &seasons);
+
+        assert_eq!(history.name, "Player 1");
+        assert_eq!(
+            history
+                .entries
+                .iter()
+                .map(|e| (
+                    e.season.as_str(),
+                    e.team.as_str(),
+                    e.statistics.goals_scored
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("21/22", "CHE", 3),
+                ("22/23", "CHE", 1),
+                ("22/23", "MCI", 2),
+                ("23/24", "CHE", 22)
+            ]
+        );
+        assert_eq!(history.total().goals_scored, 28);
+        assert_eq!(history.teams(), vec!["CHE", "MCI"]);
+    }
+}
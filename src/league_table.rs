@@ -0,0 +1,89 @@
+use crate::types::{SeasonStandings, StandingRow, StandingType};
+
+pub struct LeagueTable(SeasonStandings);
+
+impl LeagueTable {
+    pub fn new(standings: SeasonStandings) -> Self {
+        Self(standings)
+    }
+    // Leagues have a single group, but the rows are merged in case there are more
+    pub fn rows(&self, kind: StandingType) -> impl Iterator<Item = StandingRow> {
+        let mut rows: Vec<StandingRow> = self
+            .0
+            .standings
+            .iter()
+            .filter(|standing| standing.kind == kind)
+            .flat_map(|standing| standing.groups.iter())
+            .flat_map(|group| group.standings.iter().cloned())
+            .collect();
+        rows.sort_by_key(|row| row.rank);
+        rows.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::league_table::LeagueTable;
+    use crate::types::{
+        CompetitorRef, SeasonStandings, Standing, StandingGroup, StandingRow, StandingType,
+    };
+
+    fn mock_row(rank: usize, abbreviation: &str, points: usize) -> StandingRow {
+        StandingRow {
+            rank,
+            competitor: CompetitorRef {
+                id: Default::default(),
+                name: Default::default(),
+                abbreviation: abbreviation.to_string(),
+            },
+            played: Default::default(),
+            win: Default::default(),
+            draw: Default::default(),
+            loss: Default::default(),
+            goals_for: Default::default(),
+            goals_against: Default::default(),
+            goals_diff: Default::default(),
+            points,
+        }
+    }
+
+    #[test]
+    fn test_rows() {
+        let table = LeagueTable::new(SeasonStandings {
+            standings: vec![
+                Standing {
+                    kind: StandingType::Total,
+                    groups: vec![StandingGroup {
+                        standings: vec![
+                            mock_row(2, "ARS", 89),
+                            mock_row(1, "MCI", 91),
+                            mock_row(3, "LIV", 82),
+                        ],
+                    }],
+                },
+                Standing {
+                    kind: StandingType::Home,
+                    groups: vec![StandingGroup {
+                        standings: vec![mock_row(1, "ARS", 47), mock_row(2, "MCI", 46)],
+                    }],
+                },
+            ],
+        });
+
+        assert_eq!(
+            table
+                .rows(StandingType::Total)
+                .map(|row| (row.rank, row.competitor.abbreviation))
+                .collect::<Vec<_>>(),
+            vec![(1, "MCI".into()), (2, "ARS".into()), (3, "LIV".into())]
+        );
+        assert_eq!(
+            table
+                .rows(StandingType::Home)
+                .map(|row| row.points)
+                .collect::<Vec<_>>(),
+            vec![47, 46]
+        );
+        assert_eq!(table.rows(StandingType::Away).count(), 0);
+    }
+}
@@ -1,7 +1,72 @@
-pub mod api_client;
-pub mod cached_client;
-pub mod client;
-pub mod cmd;
-pub mod error;
-pub mod top_players;
-pub mod types;
+// The modules are internal, the library API is what's re-exported below
+mod api_client;
+mod cached_client;
+mod cassette;
+mod client;
+#[cfg(feature = "cli")]
+mod cmd;
+mod compare;
+#[cfg(feature = "cli")]
+mod completions;
+mod config;
+mod db;
+mod error;
+// Namespaced, as its functions are only about the files it writes
+#[cfg(feature = "export")]
+pub mod export;
+mod fixtures;
+mod history;
+mod league_table;
+#[cfg(feature = "cli")]
+mod logging;
+mod matchday;
+mod metrics;
+mod middleware;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+mod output;
+mod players;
+mod quota;
+mod seasons;
+mod secret;
+mod server;
+mod snapshot;
+mod top_players;
+mod top_teams;
+#[cfg(feature = "cli")]
+mod tui;
+#[cfg(test)]
+mod test_support;
+mod types;
+mod watch;
+
+pub use crate::api_client::{SportsApiClient, SportsApiClientBuilder};
+pub use crate::cached_client::{CachedClient, CachedSeason};
+pub use crate::client::{Client, Offline};
+#[cfg(feature = "cli")]
+pub use crate::cmd::Cli;
+pub use crate::compare::{ComparedValue, Comparison, ComparisonRow};
+pub use crate::config::{AccessLevel, Config, Settings, Source};
+pub use crate::db::{Database, SyncSummary};
+pub use crate::error::{ConfigError, EnvVarError, Error, IOError, JSONError};
+pub use crate::fixtures::{FixtureFilter, Fixtures};
+pub use crate::history::{HistoryEntry, PlayerHistory, SeasonPlayers};
+pub use crate::league_table::LeagueTable;
+pub use crate::matchday::Matchday;
+pub use crate::metrics::Metrics;
+pub use crate::middleware::{Metered, Quota, RateLimit, Retry, RetryPolicy};
+pub use crate::output::{write_tables, OutputFormat, Table};
+pub use crate::players::SeasonPlayer;
+pub use crate::quota::{Ledger, Usage};
+pub use crate::secret::Secret;
+pub use crate::server::{Server, ServerOptions};
+pub use crate::snapshot::{Snapshot, SnapshotDiff, Snapshots};
+pub use crate::top_players::{Leaderboard, TopPlayers};
+pub use crate::top_teams::{TeamColumn, TopTeams};
+pub use crate::types::{
+    CompetitionSeasons, Competitor, CompetitorRef, CompetitorStats, MatchCompetitor, MatchStatus,
+    MatchSummary, Player, PlayerStats, Qualifier, Round, Season, SeasonCompetitor,
+    SeasonCompetitors, SeasonSchedules, SeasonStandings, SportEvent, SportEventContext,
+    SportEventStatus, Standing, StandingGroup, StandingRow, StandingType, TeamStats,
+};
+pub use crate::watch::{LiveEntry, LiveLeaderboard, Movement};
@@ -0,0 +1,84 @@
+use std::io;
+
+use clap::{ArgAction, Args, ValueEnum};
+use tracing_subscriber::EnvFilter;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    // One JSON object per line, for log pipelines
+    Json,
+}
+
+// Logs are written to stderr, so they never mix with the command results
+#[derive(Args, Clone, Debug, Default, PartialEq)]
+pub struct Logging {
+    /// Logs more details, -v for cache hits and requests, -vv for everything. RUST_LOG overrides it
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+    /// Only logs errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Format of the logs
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
+
+impl Logging {
+    fn directives(&self) -> &'static str {
+        match (self.quiet, self.verbose) {
+            (true, _) => "error",
+            (false, 0) => "warn,matchday=info",
+            (false, 1) => "warn,matchday=debug",
+            (false, _) => "info,matchday=trace",
+        }
+    }
+
+    // Does nothing if a subscriber was already set
+    pub fn init(&self) {
+        let filter =
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(self.directives()));
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(io::stderr);
+
+        let _ = match self.log_format {
+            LogFormat::Json => builder.json().try_init(),
+            // Plain progress messages unless asked for details
+            LogFormat::Text if self.verbose == 0 => {
+                builder.without_time().with_target(false).try_init()
+            }
+            LogFormat::Text => builder.try_init(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use crate::logging::{LogFormat, Logging};
+
+    #[derive(Parser)]
+    struct Args {
+        #[command(flatten)]
+        logging: Logging,
+    }
+
+    fn parse(args: &[&str]) -> Logging {
+        let mut argv = vec!["matchday"];
+        argv.extend(args);
+        Args::try_parse_from(argv).unwrap().logging
+    }
+
+    #[test]
+    fn test_directives() {
+        assert_eq!(parse(&[]).directives(), "warn,matchday=info");
+        assert_eq!(parse(&["-v"]).directives(), "warn,matchday=debug");
+        assert_eq!(parse(&["-vv"]).directives(), "info,matchday=trace");
+        assert_eq!(parse(&["--quiet"]).directives(), "error");
+        assert_eq!(parse(&["--log-format", "json"]).log_format, LogFormat::Json);
+        assert!(Args::try_parse_from(["matchday", "-q", "-v"]).is_err());
+    }
+}
@@ -1,15 +1,16 @@
 use clap::Parser;
 use dotenv::dotenv;
 
-use matchday::cmd::Cmd;
+use matchday::Cli;
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+// Without #[tokio::main], so completing from the cache can start its own runtime
+fn main() -> anyhow::Result<()> {
     dotenv().ok();
+    Cli::complete();
 
-    let cmd = Cmd::parse();
+    let cli = Cli::parse();
 
-    cmd.run().await?;
+    tokio::runtime::Runtime::new()?.block_on(cli.run())?;
 
     Ok(())
 }
@@ -0,0 +1,327 @@
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::cached_client::CachedClient;
+use crate::client::{self, Client, Offline};
+use crate::compare::{compare, Comparison};
+use crate::config::Config;
+use crate::db::{Database, SyncSummary};
+use crate::error::Error;
+use crate::fixtures::{FixtureFilter, Fixtures};
+use crate::history::{PlayerHistory, SeasonPlayers};
+use crate::league_table::LeagueTable;
+use crate::metrics::Metrics;
+use crate::players::{find_player, season_players, SeasonPlayer};
+use crate::quota::{Ledger, Usage};
+use crate::seasons::{find_season, find_seasons};
+use crate::snapshot::Snapshot;
+use crate::top_players::TopPlayers;
+use crate::top_teams::{TeamColumn, TopTeams};
+use crate::types::{Competitor, MatchSummary, Season};
+
+// Entry point for using matchday as a library, the CLI is built on top of it.
+// Every method returns typed results and never prints. Seasons are given by
+// ID, such as "sr:season:105353", or by year, such as "23/24".
+pub struct Matchday<C = CachedClient<Box<dyn Client>>> {
+    client: C,
+    metrics: Arc<Metrics>,
+    ledger: Option<Arc<Ledger>>,
+}
+
+impl Matchday {
+    // Same stack as the CLI: cached, recorded or replayed, retried, rate
+    // limited, metered and counted against the quota
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let metrics = Arc::new(Metrics::new());
+        let ledger = Arc::new(Ledger::from_config(config));
+        let client = client::from_config(config, metrics.clone(), ledger.clone())?;
+        let client = CachedClient::from_config(client, config).with_metrics(metrics.clone());
+        Ok(Self {
+            client,
+            metrics,
+            // Replaying a cassette makes no calls
+            ledger: config.replay.is_none().then_some(ledger),
+        })
+    }
+
+    // Only reads the cache, where nothing expires, without any API call. What
+    // isn't cached fails with `Error::NotCached`.
+    pub fn offline(config: &Config) -> Self {
+        let metrics = Arc::new(Metrics::new());
+        let client: Box<dyn Client> = Box::new(Offline);
+        let client = CachedClient::from_config(client, config)
+            .with_offline(true)
+            .with_metrics(metrics.clone());
+        Self {
+            client,
+            metrics,
+            ledger: None,
+        }
+    }
+}
+
+impl<C: Client> Matchday<CachedClient<C>> {
+    pub fn clear_cache(&self) -> io::Result<()> {
+        self.client.clear()
+    }
+
+    // Fetches the season data again, even if the cache didn't expire
+    pub async fn refresh(&self, season: &str) -> Result<(), Error> {
+        let season = self.season_id(season).await?;
+        self.client.refresh(&season).await
+    }
+
+    // How long the cached data is used before it's fetched again, forever without one
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.client.ttl()
+    }
+
+    // Fetches again only the season data missing or older than `max_age`
+    pub async fn refresh_stale(&self, season: &str, max_age: Duration) -> Result<(), Error> {
+        let season = self.season_id(season).await?;
+        self.client.refresh_stale(&season, max_age).await
+    }
+
+    // API calls a stale refresh makes at most
+    pub async fn stale_calls(&self, season: &str, max_age: Duration) -> Result<u64, Error> {
+        let season = self.season_id(season).await?;
+        self.client.stale_calls(&season, max_age)
+    }
+
+    // Fetches again only the stats of the given teams missing or older than `max_age`
+    pub async fn refresh_stale_stats(
+        &self,
+        season: &str,
+        teams: &[String],
+        max_age: Duration,
+    ) -> Result<(), Error> {
+        let season = self.season_id(season).await?;
+        self.client
+            .refresh_stale_stats(&season, teams, max_age)
+            .await
+    }
+
+    // API calls a stale refresh of the stats of the given teams makes at most
+    pub async fn stale_stats_calls(
+        &self,
+        season: &str,
+        teams: &[String],
+        max_age: Duration,
+    ) -> Result<u64, Error> {
+        let season = self.season_id(season).await?;
+        self.client.stale_stats_calls(&season, teams, max_age)
+    }
+
+    // API calls a refresh makes at most. Looking up a season by year may
+    // fetch the seasons first, if they weren't cached.
+    pub async fn refresh_calls(&self, season: &str) -> Result<u64, Error> {
+        let season = self.season_id(season).await?;
+        self.client.refresh_calls(&season)
+    }
+}
+
+impl<C: Client> Matchday<C> {
+    pub fn with_client(client: C) -> Self {
+        Self {
+            client,
+            metrics: Arc::new(Metrics::new()),
+            ledger: None,
+        }
+    }
+
+    // The metrics the layers of the client record into, see `Metrics`
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    // The ledger the client records its calls in, see `Quota`
+    pub fn with_ledger(mut self, ledger: Arc<Ledger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    // Calls made this month, none if they aren't tracked
+    pub fn quota(&self) -> Result<Option<Usage>, Error> {
+        self.ledger
+            .as_ref()
+            .map(|ledger| ledger.usage())
+            .transpose()
+    }
+
+    pub async fn seasons(&self) -> Result<Vec<Season>, Error> {
+        Ok(self.client.fetch_seasons().await?.seasons)
+    }
+
+    // ID of the season, only years are looked up in the competition seasons
+    pub async fn season_id(&self, season: &str) -> Result<String, Error> {
+        if season.starts_with("sr:season:") {
+            return Ok(season.to_string());
+        }
+        Ok(find_season(&self.client.fetch_seasons().await?, season)?
+            .id
+            .clone())
+    }
+
+    pub async fn teams(&self, season: &str) -> Result<Vec<Competitor>, Error> {
+        let season = self.season_id(season).await?;
+        let competitors = self.client.fetch_competitors(&season).await?;
+        let mut teams = Vec::with_capacity(20);
+
+        for competitor in competitors.season_competitors {
+            let stats = self
+                .client
+                .fetch_competitor_stats(&season, &competitor.id)
+                .await?;
+            teams.push(stats.competitor);
+        }
+
+        Ok(teams)
+    }
+
+    pub async fn top_teams(
+        &self,
+        season: &str,
+        column: TeamColumn,
+    ) -> Result<Vec<Competitor>, Error> {
+        let teams = self.teams(season).await?;
+        Ok(TopTeams::new(teams).sort_by(column).collect())
+    }
+
+    // Players who moved mid season are listed once for each club if split by team
+    pub async fn players(
+        &self,
+        season: &str,
+        split_by_team: bool,
+    ) -> Result<Vec<SeasonPlayer>, Error> {
+        let teams = self.teams(season).await?;
+        Ok(season_players(teams, split_by_team))
+    }
+
+    // Both at once, as the players are read from the stats of the teams
+    pub async fn teams_and_players(
+        &self,
+        season: &str,
+        split_by_team: bool,
+    ) -> Result<(Vec<Competitor>, Vec<SeasonPlayer>), Error> {
+        let teams = self.teams(season).await?;
+        let players = season_players(teams.clone(), split_by_team);
+        Ok((teams, players))
+    }
+
+    pub async fn top_scorers(
+        &self,
+        season: &str,
+        limit: usize,
+    ) -> Result<Vec<SeasonPlayer>, Error> {
+        let players = self.players(season, false).await?;
+        Ok(TopPlayers::new(players, limit).by_goals().collect())
+    }
+
+    pub async fn top_assists(
+        &self,
+        season: &str,
+        limit: usize,
+    ) -> Result<Vec<SeasonPlayer>, Error> {
+        let players = self.players(season, false).await?;
+        Ok(TopPlayers::new(players, limit).by_assists().collect())
+    }
+
+    // Ordered first by goals then assists
+    pub async fn top_players(
+        &self,
+        season: &str,
+        limit: usize,
+    ) -> Result<Vec<SeasonPlayer>, Error> {
+        let players = self.players(season, false).await?;
+        Ok(TopPlayers::new(players, limit).by_both().collect())
+    }
+
+    // The players of the season as they are now, to compare with later ones
+    pub async fn snapshot(&self, season: &str, name: &str) -> Result<Snapshot, Error> {
+        let season = self.season_id(season).await?;
+        Ok(Snapshot {
+            name: name.to_string(),
+            players: self.players(&season, false).await?,
+            season,
+            taken_at: Utc::now(),
+        })
+    }
+
+    pub async fn standings(&self, season: &str) -> Result<LeagueTable, Error> {
+        let season = self.season_id(season).await?;
+        let standings = self.client.fetch_standings(&season).await?;
+        Ok(LeagueTable::new(standings))
+    }
+
+    pub async fn fixtures(
+        &self,
+        season: &str,
+        filter: &FixtureFilter,
+    ) -> Result<Vec<MatchSummary>, Error> {
+        let season = self.season_id(season).await?;
+        let schedules = self.client.fetch_schedules(&season).await?;
+        Ok(Fixtures::new(schedules.schedules).filter(filter).collect())
+    }
+
+    // Players are found by name or ID, and ranked against the whole league
+    pub async fn compare(&self, season: &str, players: &[&str]) -> Result<Comparison, Error> {
+        let league = self.players(season, false).await?;
+        let players = players
+            .iter()
+            .map(|query| find_player(&league, query).cloned())
+            .collect::<Result<Vec<SeasonPlayer>, _>>()?;
+        let rows = compare(&players, &league);
+        Ok(Comparison { players, rows })
+    }
+
+    // Seasons is a single one or an inclusive range, such as "21/22..23/24"
+    pub async fn history(&self, player: &str, seasons: &str) -> Result<PlayerHistory, Error> {
+        let seasons_players = self.seasons_players(seasons).await?;
+
+        let players: Vec<SeasonPlayer> = seasons_players
+            .iter()
+            .flat_map(|season| season.players.iter().cloned())
+            .collect();
+        let found = find_player(&players, player)?;
+        Ok(PlayerHistory::new(&found.id, &seasons_players))
+    }
+
+    // The players of each season in the range, split by the team they played for
+    pub async fn seasons_players(&self, seasons: &str) -> Result<Vec<SeasonPlayers>, Error> {
+        let seasons = find_seasons(&self.client.fetch_seasons().await?, seasons)?;
+        let mut seasons_players = Vec::with_capacity(seasons.len());
+
+        for season in seasons {
+            let players = self.players(&season.id, true).await?;
+            seasons_players.push(SeasonPlayers { season, players });
+        }
+
+        Ok(seasons_players)
+    }
+
+    // Fetches the teams of every season in the range before writing any of
+    // them, so a failed request leaves the database as it was
+    pub async fn sync(&self, db: &mut Database, seasons: &str) -> Result<SyncSummary, Error> {
+        let competition = self.client.fetch_seasons().await?;
+        let synced = find_seasons(&competition, seasons)?;
+        let mut teams = Vec::with_capacity(synced.len());
+
+        for season in synced {
+            teams.push((season.id.clone(), self.teams(&season.id).await?));
+        }
+
+        db.sync(&competition.seasons, &teams)
+    }
+}
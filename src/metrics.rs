@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+use crate::error::{Error, IOError};
+
+// Sportradar meters the quota per call, so these count the calls made to the
+// API and the ones the cache avoided. The layers of a stack share them through
+// an `Arc`, labelled by endpoint: seasons, competitors, competitor_stats,
+// standings and schedules.
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    retries: IntCounterVec,
+    cache_hits: IntCounterVec,
+    cache_misses: IntCounterVec,
+    cache_writes: IntCounterVec,
+    latency: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let counter = |name: &str, help: &str, labels: &[&str]| {
+            IntCounterVec::new(Opts::new(name, help), labels).expect("valid metric")
+        };
+        let metrics = Self {
+            registry: Registry::new(),
+            requests: counter(
+                "matchday_api_requests_total",
+                "Requests made to the Sportradar API, including the retried ones",
+                &["endpoint", "status"],
+            ),
+            retries: counter(
+                "matchday_api_retries_total",
+                "Failed requests that were retried",
+                &["endpoint"],
+            ),
+            cache_hits: counter(
+                "matchday_cache_hits_total",
+                "Data served from the cache, without any request",
+                &["endpoint"],
+            ),
+            cache_misses: counter(
+                "matchday_cache_misses_total",
+                "Data missing or expired in the cache, fetched from the API",
+                &["endpoint"],
+            ),
+            cache_writes: counter(
+                "matchday_cache_writes_total",
+                "Files written to the cache",
+                &["endpoint"],
+            ),
+            latency: HistogramVec::new(
+                HistogramOpts::new(
+                    "matchday_api_request_duration_seconds",
+                    "Duration of the requests made to the Sportradar API",
+                ),
+                &["endpoint"],
+            )
+            .expect("valid metric"),
+        };
+
+        for collector in [
+            &metrics.requests,
+            &metrics.retries,
+            &metrics.cache_hits,
+            &metrics.cache_misses,
+            &metrics.cache_writes,
+        ] {
+            metrics
+                .registry
+                .register(Box::new(collector.clone()))
+                .expect("unique metric");
+        }
+        metrics
+            .registry
+            .register(Box::new(metrics.latency.clone()))
+            .expect("unique metric");
+
+        metrics
+    }
+
+    // Status is the HTTP status code, or "error" if there was no response
+    pub fn request(&self, endpoint: &str, status: &str, duration: Duration) {
+        self.requests.with_label_values(&[endpoint, status]).inc();
+        self.latency
+            .with_label_values(&[endpoint])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn retry(&self, endpoint: &str) {
+        self.retries.with_label_values(&[endpoint]).inc();
+    }
+
+    pub fn cache_hit(&self, endpoint: &str) {
+        self.cache_hits.with_label_values(&[endpoint]).inc();
+    }
+
+    pub fn cache_miss(&self, endpoint: &str) {
+        self.cache_misses.with_label_values(&[endpoint]).inc();
+    }
+
+    pub fn cache_write(&self, endpoint: &str) {
+        self.cache_writes.with_label_values(&[endpoint]).inc();
+    }
+
+    // Prometheus text format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding doesn't fail");
+        String::from_utf8(buffer).expect("text encoding is UTF-8")
+    }
+
+    pub fn write_file(&self, file: &Path) -> Result<(), Error> {
+        fs::write(file, self.render())
+            .map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+        Ok(())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::metrics::Metrics;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::new();
+        metrics.request("seasons", "200", Duration::from_millis(120));
+        metrics.request("seasons", "429", Duration::from_millis(20));
+        metrics.retry("seasons");
+        metrics.cache_miss("seasons");
+        metrics.cache_write("seasons");
+        metrics.cache_hit("seasons");
+        metrics.cache_hit("seasons");
+
+        let rendered = metrics.render();
+        for line in [
+            r#"matchday_api_requests_total{endpoint="seasons",status="200"} 1"#,
+            r#"matchday_api_requests_total{endpoint="seasons",status="429"} 1"#,
+            r#"matchday_api_retries_total{endpoint="seasons"} 1"#,
+            r#"matchday_cache_hits_total{endpoint="seasons"} 2"#,
+            r#"matchday_cache_misses_total{endpoint="seasons"} 1"#,
+            r#"matchday_cache_writes_total{endpoint="seasons"} 1"#,
+            r#"matchday_api_request_duration_seconds_bucket{endpoint="seasons",le="0.25"} 2"#,
+            r#"matchday_api_request_duration_seconds_count{endpoint="seasons"} 2"#,
+        ] {
+            assert!(rendered.contains(line), "missing {line} in\n{rendered}");
+        }
+    }
+}
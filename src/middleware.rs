@@ -0,0 +1,476 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tokio_retry::strategy::{jitter, ExponentialBackoff};
+use tracing::{debug, warn};
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::quota::Ledger;
+use crate::types::{
+    CompetitionSeasons, CompetitorStats, SeasonCompetitors, SeasonSchedules, SeasonStandings,
+};
+
+// Clients wrapping another client, so they can be stacked in any order:
+//
+// CachedClient -> Retry -> RateLimit -> Metered -> Quota -> SportsApiClient
+//
+// With the rate limit below the retries, the retried requests are spaced too,
+// and right above the API client every attempt is metered and counted
+// against the quota. The cassette isn't a layer, the API client records its
+// responses into it before they're parsed, see `Cassette`.
+
+// How failed requests are retried, the delays between attempts grow
+// exponentially from the base (20ms, 400ms, 8s, ...) with some jitter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub retries: usize,
+    pub base_millis: u64,
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn strategy(&self) -> impl Iterator<Item = Duration> {
+        ExponentialBackoff::from_millis(self.base_millis)
+            .map(jitter)
+            .take(self.retries)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            base_millis: 20,
+        }
+    }
+}
+
+pub struct Retry<C> {
+    client: C,
+    policy: RetryPolicy,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl<C: Client> Retry<C> {
+    pub fn new(client: C, policy: RetryPolicy) -> Self {
+        Self {
+            client,
+            policy,
+            metrics: None,
+        }
+    }
+
+    // Counts the retried requests
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    async fn retry<T, F, Fut>(&self, endpoint: &str, mut request: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut attempt = 0;
+        let action = || {
+            attempt += 1;
+            let attempt = attempt;
+            if let Some(metrics) = self.metrics.as_ref().filter(|_| attempt > 1) {
+                metrics.retry(endpoint);
+            }
+            let response = request();
+            async move {
+                response.await.inspect_err(
+                    |e| warn!(endpoint, attempt, retries = self.policy.retries, error = %e, "request failed"),
+                )
+            }
+        };
+        tokio_retry::RetryIf::spawn(self.policy.strategy(), action, is_transient).await
+    }
+}
+
+// Only the failures that may not happen again are retried: rate limiting,
+// server errors, timeouts and connection errors
+fn is_transient(e: &Error) -> bool {
+    match e {
+        Error::Request(e) => match e.status() {
+            Some(status) => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+            None => e.is_timeout() || e.is_connect(),
+        },
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl<C: Client> Client for Retry<C> {
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        self.retry("seasons", || self.client.fetch_seasons()).await
+    }
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        self.retry("competitors", || self.client.fetch_competitors(season))
+            .await
+    }
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        self.retry("competitor_stats", || {
+            self.client.fetch_competitor_stats(season, id)
+        })
+        .await
+    }
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        self.retry("standings", || self.client.fetch_standings(season))
+            .await
+    }
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        self.retry("schedules", || self.client.fetch_schedules(season))
+            .await
+    }
+}
+
+pub struct RateLimit<C> {
+    client: C,
+    // Spacing between requests, none without a limit
+    min_interval: Option<Duration>,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl<C: Client> RateLimit<C> {
+    // Requests per second, zero disables it. Fails if it's negative, or so
+    // small that the spacing between requests doesn't fit in a `Duration`.
+    pub fn new(client: C, rate_limit: f64) -> Result<Self, Error> {
+        let min_interval = if rate_limit == 0.0 {
+            None
+        } else {
+            Some(
+                Duration::try_from_secs_f64(1.0 / rate_limit)
+                    .map_err(|_| Error::InvalidRateLimit(rate_limit))?,
+            )
+        };
+        Ok(Self {
+            client,
+            min_interval,
+            last_request: Mutex::new(None),
+        })
+    }
+
+    // Waits until the rate limit allows another request
+    async fn throttle(&self) {
+        let mut last_request = self.last_request.lock().await;
+        if let (Some(min_interval), Some(last)) = (self.min_interval, *last_request) {
+            let wait = (last + min_interval).saturating_duration_since(Instant::now());
+            if !wait.is_zero() {
+                debug!(wait_ms = wait.as_millis() as u64, "rate limited");
+                tokio::time::sleep(wait).await;
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+}
+
+#[async_trait]
+impl<C: Client> Client for RateLimit<C> {
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        self.throttle().await;
+        self.client.fetch_seasons().await
+    }
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        self.throttle().await;
+        self.client.fetch_competitors(season).await
+    }
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        self.throttle().await;
+        self.client.fetch_competitor_stats(season, id).await
+    }
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        self.throttle().await;
+        self.client.fetch_standings(season).await
+    }
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        self.throttle().await;
+        self.client.fetch_schedules(season).await
+    }
+}
+
+// Counts every request made by the client, by endpoint and status, and how long it took
+pub struct Metered<C> {
+    client: C,
+    metrics: Arc<Metrics>,
+}
+
+impl<C: Client> Metered<C> {
+    pub fn new(client: C, metrics: Arc<Metrics>) -> Self {
+        Self { client, metrics }
+    }
+
+    async fn measure<T>(
+        &self,
+        endpoint: &str,
+        request: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        let start = Instant::now();
+        let response = request.await;
+        let status = match &response {
+            Ok(_) => "200".to_string(),
+            Err(Error::Request(e)) => e
+                .status()
+                .map_or("error".to_string(), |status| status.as_str().to_string()),
+            Err(_) => "error".to_string(),
+        };
+        self.metrics.request(endpoint, &status, start.elapsed());
+        response
+    }
+}
+
+#[async_trait]
+impl<C: Client> Client for Metered<C> {
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        self.measure("seasons", self.client.fetch_seasons()).await
+    }
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        self.measure("competitors", self.client.fetch_competitors(season))
+            .await
+    }
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        self.measure(
+            "competitor_stats",
+            self.client.fetch_competitor_stats(season, id),
+        )
+        .await
+    }
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        self.measure("standings", self.client.fetch_standings(season))
+            .await
+    }
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        self.measure("schedules", self.client.fetch_schedules(season))
+            .await
+    }
+}
+
+// Records every request made by the client in the quota ledger, except the
+// ones that never reached the API
+pub struct Quota<C> {
+    client: C,
+    ledger: Arc<Ledger>,
+}
+
+impl<C: Client> Quota<C> {
+    pub fn new(client: C, ledger: Arc<Ledger>) -> Self {
+        Self { client, ledger }
+    }
+
+    async fn record<T>(
+        &self,
+        endpoint: &str,
+        request: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        let response = request.await;
+        if !matches!(&response, Err(Error::Request(e)) if e.is_connect()) {
+            // The call was made already, failing the command wouldn't undo it
+            if let Err(error) = self.ledger.record(endpoint) {
+                warn!(endpoint, %error, "failed to record the request in the quota ledger");
+            }
+        }
+        response
+    }
+}
+
+#[async_trait]
+impl<C: Client> Client for Quota<C> {
+    async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+        self.record("seasons", self.client.fetch_seasons()).await
+    }
+    async fn fetch_competitors(&self, season: &str) -> Result<SeasonCompetitors, Error> {
+        self.record("competitors", self.client.fetch_competitors(season))
+            .await
+    }
+    async fn fetch_competitor_stats(
+        &self,
+        season: &str,
+        id: &str,
+    ) -> Result<CompetitorStats, Error> {
+        self.record(
+            "competitor_stats",
+            self.client.fetch_competitor_stats(season, id),
+        )
+        .await
+    }
+    async fn fetch_standings(&self, season: &str) -> Result<SeasonStandings, Error> {
+        self.record("standings", self.client.fetch_standings(season))
+            .await
+    }
+    async fn fetch_schedules(&self, season: &str) -> Result<SeasonSchedules, Error> {
+        self.record("schedules", self.client.fetch_schedules(season))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use async_trait::async_trait;
+
+    use crate::client::Client;
+    use crate::error::Error;
+    use crate::metrics::Metrics;
+    use crate::middleware::{Quota, RateLimit, Retry, RetryPolicy};
+    use crate::quota::Ledger;
+    use crate::types::{
+        CompetitionSeasons, CompetitorStats, SeasonCompetitors, SeasonSchedules, SeasonStandings,
+    };
+
+    // Fails the first requests with the status, then answers with empty seasons
+    #[derive(Default)]
+    struct FlakyClient {
+        failures: usize,
+        status: u16,
+        requests: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Client for FlakyClient {
+        async fn fetch_seasons(&self) -> Result<CompetitionSeasons, Error> {
+            if self.requests.fetch_add(1, Ordering::SeqCst) < self.failures {
+                let response = axum::http::Response::builder()
+                    .status(self.status)
+                    .body("")
+                    .unwrap();
+                return Err(reqwest::Response::from(response)
+                    .error_for_status()
+                    .unwrap_err()
+                    .into());
+            }
+            Ok(CompetitionSeasons { seasons: vec![] })
+        }
+        async fn fetch_competitors(&self, _season: &str) -> Result<SeasonCompetitors, Error> {
+            unimplemented!()
+        }
+        async fn fetch_competitor_stats(
+            &self,
+            _season: &str,
+            _id: &str,
+        ) -> Result<CompetitorStats, Error> {
+            unimplemented!()
+        }
+        async fn fetch_standings(&self, _season: &str) -> Result<SeasonStandings, Error> {
+            unimplemented!()
+        }
+        async fn fetch_schedules(&self, _season: &str) -> Result<SeasonSchedules, Error> {
+            unimplemented!()
+        }
+    }
+
+    fn flaky(failures: usize) -> FlakyClient {
+        failing(failures, 503)
+    }
+
+    fn failing(failures: usize, status: u16) -> FlakyClient {
+        FlakyClient {
+            failures,
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let policy = RetryPolicy {
+            retries: 2,
+            base_millis: 1,
+        };
+
+        let metrics = Arc::new(Metrics::new());
+        let client = Retry::new(flaky(2), policy).with_metrics(metrics.clone());
+        assert!(client.fetch_seasons().await.is_ok());
+        assert_eq!(client.client.requests.load(Ordering::SeqCst), 3);
+        assert!(metrics
+            .render()
+            .contains(r#"matchday_api_retries_total{endpoint="seasons"} 2"#));
+
+        let client = Retry::new(flaky(3), policy);
+        assert!(client.fetch_seasons().await.is_err());
+        assert_eq!(client.client.requests.load(Ordering::SeqCst), 3);
+
+        let client = Retry::new(failing(1, 429), policy);
+        assert!(client.fetch_seasons().await.is_ok());
+        assert_eq!(client.client.requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_client_errors() {
+        let policy = RetryPolicy {
+            retries: 2,
+            base_millis: 1,
+        };
+
+        // the same request would fail again
+        let client = Retry::new(failing(1, 404), policy);
+        assert!(client.fetch_seasons().await.is_err());
+        assert_eq!(client.client.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        // a request every 50ms
+        let client = RateLimit::new(flaky(0), 20.0).unwrap();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            client.fetch_seasons().await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        for rate_limit in [1e-300, -1.0, f64::NAN] {
+            assert!(
+                RateLimit::new(flaky(0), rate_limit).is_err(),
+                "{rate_limit}"
+            );
+        }
+        assert!(RateLimit::new(flaky(0), 0.0)
+            .unwrap()
+            .min_interval
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quota() {
+        let cache_dir = std::env::temp_dir().join("matchday-middleware-quota");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let ledger = Arc::new(Ledger::new(&cache_dir, None));
+
+        // failed requests count too
+        let client = Quota::new(flaky(1), ledger.clone());
+        assert!(client.fetch_seasons().await.is_err());
+        assert!(client.fetch_seasons().await.is_ok());
+        assert_eq!(ledger.usage().unwrap().endpoints["seasons"], 2);
+
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
+}
@@ -0,0 +1,155 @@
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path as UrlPath, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+
+use crate::server::Serving;
+
+// Stand-in for the Sportradar API, so the tool can be developed and tested
+// without spending the quota of an API key. Every endpoint used by the
+// SportsApiClient is answered with a fixture file, ignoring the access level,
+// version and locale, with colons replaced so the names work on every OS:
+//
+// /soccer/trial/v4/en/competitions/sr:competition:17/seasons.json
+//   -> fixtures/competitions/sr_competition_17/seasons.json
+// /soccer/trial/v4/en/seasons/<season>/competitors/<competitor>/statistics.json
+//   -> fixtures/seasons/<season>/competitors/<competitor>/statistics.json
+#[derive(Clone, Debug)]
+pub struct MockServerOptions {
+    pub fixtures: PathBuf,
+    // Delay before every response
+    pub latency: Duration,
+    // Answers every nth request with 429 Too Many Requests
+    pub rate_limit_every: Option<usize>,
+    // Answers every nth request with 503 Service Unavailable
+    pub server_error_every: Option<usize>,
+}
+
+impl Default for MockServerOptions {
+    fn default() -> Self {
+        Self {
+            fixtures: PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures")),
+            latency: Duration::ZERO,
+            rate_limit_every: None,
+            server_error_every: None,
+        }
+    }
+}
+
+struct ServerState {
+    options: MockServerOptions,
+    requests: AtomicUsize,
+}
+
+// Stops serving once dropped
+pub struct MockServer {
+    state: Arc<ServerState>,
+    serving: Serving,
+}
+
+impl MockServer {
+    // Listens on a random local port
+    pub async fn start(options: MockServerOptions) -> io::Result<Self> {
+        Self::bind(([127, 0, 0, 1], 0).into(), options).await
+    }
+
+    pub async fn bind(addr: SocketAddr, options: MockServerOptions) -> io::Result<Self> {
+        let state = Arc::new(ServerState {
+            options,
+            requests: AtomicUsize::new(0),
+        });
+        let app = Router::new()
+            .route(
+                "/soccer/:access_level/:version/:locale/*path",
+                get(serve_fixture),
+            )
+            .with_state(state.clone());
+
+        Ok(Self {
+            state,
+            serving: Serving::bind(addr, app).await?,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        self.serving.url()
+    }
+
+    // Every request received so far, including the failed ones
+    pub fn requests(&self) -> usize {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+
+    pub async fn wait(&mut self) {
+        self.serving.wait().await;
+    }
+}
+
+async fn serve_fixture(
+    State(state): State<Arc<ServerState>>,
+    UrlPath((_access_level, _version, _locale, path)): UrlPath<(String, String, String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    let request = state.requests.fetch_add(1, Ordering::SeqCst) + 1;
+    let options = &state.options;
+    let is_nth =
+        |every: Option<usize>| every.is_some_and(|every| every > 0 && request % every == 0);
+
+    tokio::time::sleep(options.latency).await;
+
+    if !headers.contains_key("x-api-key") {
+        return (StatusCode::UNAUTHORIZED, "missing x-api-key header").into_response();
+    }
+    if is_nth(options.rate_limit_every) {
+        return (StatusCode::TOO_MANY_REQUESTS, "too many requests").into_response();
+    }
+    if is_nth(options.server_error_every) {
+        return (StatusCode::SERVICE_UNAVAILABLE, "service unavailable").into_response();
+    }
+
+    match tokio::fs::read(fixture_file(&options.fixtures, &path)).await {
+        Ok(body) => ([(CONTENT_TYPE, "application/json")], body).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, format!("no fixture for {path}")).into_response(),
+    }
+}
+
+// Segments such as ".." are dropped, so only files in the fixtures folder are served
+fn fixture_file(fixtures: &Path, path: &str) -> PathBuf {
+    let mut file = fixtures.to_path_buf();
+    for segment in path.split('/') {
+        if !matches!(segment, "" | "." | "..") {
+            file.push(segment.replace(':', "_"));
+        }
+    }
+    file
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::mock_server::fixture_file;
+
+    #[test]
+    fn test_fixture_file() {
+        let fixtures = Path::new("fixtures");
+
+        assert_eq!(
+            fixture_file(fixtures, "seasons/sr:season:105353/competitors.json"),
+            PathBuf::from("fixtures/seasons/sr_season_105353/competitors.json")
+        );
+        assert_eq!(
+            fixture_file(fixtures, "seasons/../../.env"),
+            PathBuf::from("fixtures/seasons/.env")
+        );
+    }
+}
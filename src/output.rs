@@ -0,0 +1,210 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    // Files written by `export`, the other commands refuse them
+    #[cfg(feature = "export")]
+    Parquet,
+    #[cfg(feature = "export")]
+    Arrow,
+}
+
+impl OutputFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            #[cfg(feature = "export")]
+            OutputFormat::Parquet => "parquet",
+            #[cfg(feature = "export")]
+            OutputFormat::Arrow => "arrow",
+        }
+    }
+}
+
+pub struct Table {
+    pub title: Option<String>,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    // Printed below the rows, only in the table format
+    pub note: Option<String>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            title: None,
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+            note: None,
+        }
+    }
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+    pub fn push(&mut self, row: Vec<Value>) {
+        self.rows.push(row);
+    }
+
+    fn write_table(&self, out: &mut impl Write) -> io::Result<()> {
+        if let Some(title) = &self.title {
+            writeln!(out, "{title}")?;
+        }
+        writeln!(out, "{}", self.headers.join(" | "))?;
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| match cell {
+                    Value::String(s) => s.clone(),
+                    Value::Null => "-".into(),
+                    other => other.to_string(),
+                })
+                .collect();
+            writeln!(out, " {}", cells.join(" | "))?;
+        }
+        if let Some(note) = &self.note {
+            writeln!(out, "{note}")?;
+        }
+        Ok(())
+    }
+
+    // Rows become objects, keyed by the snake cased headers
+    pub fn to_json(&self) -> Value {
+        let keys: Vec<String> = self.headers.iter().map(|h| json_key(h)).collect();
+        Value::Array(
+            self.rows
+                .iter()
+                .map(|row| {
+                    Value::Object(
+                        keys.iter()
+                            .cloned()
+                            .zip(row.iter().cloned())
+                            .collect::<Map<String, Value>>(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+fn json_key(header: &str) -> String {
+    let mut key = String::with_capacity(header.len());
+    for c in header.chars() {
+        if c.is_alphanumeric() {
+            key.extend(c.to_lowercase());
+        } else if !key.is_empty() && !key.ends_with('_') {
+            key.push('_');
+        }
+    }
+    key.trim_end_matches('_').to_string()
+}
+
+// A single table is written as a JSON array, several of them as an object keyed by title
+pub fn write_tables(
+    out: &mut impl Write,
+    format: OutputFormat,
+    tables: &[Table],
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Table => {
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                table.write_table(out)?;
+            }
+        }
+        OutputFormat::Json => {
+            let json = match tables {
+                [table] => table.to_json(),
+                _ => Value::Object(
+                    tables
+                        .iter()
+                        .map(|t| (json_key(t.title.as_deref().unwrap_or("")), t.to_json()))
+                        .collect(),
+                ),
+            };
+            writeln!(out, "{json:#}")?;
+        }
+        // Export writes them into its own file, leaving nothing else to write
+        #[cfg(feature = "export")]
+        OutputFormat::Parquet | OutputFormat::Arrow if tables.is_empty() => {}
+        #[cfg(feature = "export")]
+        OutputFormat::Parquet | OutputFormat::Arrow => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("the {} format is only written by export", format.as_str()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::output::{write_tables, OutputFormat, Table};
+
+    fn mock_table() -> Table {
+        let mut table = Table::new(&["Goals", "Player Name", "GF 1H"]);
+        table.push(vec![json!(27), json!("Haaland, Erling"), json!(null)]);
+        table.push(vec![json!(22), json!("Palmer, Cole"), json!(3)]);
+        table
+    }
+
+    fn render(format: OutputFormat, tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, format, tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_table_format() {
+        assert_eq!(
+            render(OutputFormat::Table, &[mock_table().with_note("* note")]),
+            "Goals | Player Name | GF 1H\n 27 | Haaland, Erling | -\n 22 | Palmer, Cole | 3\n* note\n"
+        );
+        assert_eq!(
+            render(
+                OutputFormat::Table,
+                &[mock_table().with_title("Home"), mock_table().with_title("Away")]
+            ),
+            "Home\nGoals | Player Name | GF 1H\n 27 | Haaland, Erling | -\n 22 | Palmer, Cole | 3\n\nAway\nGoals | Player Name | GF 1H\n 27 | Haaland, Erling | -\n 22 | Palmer, Cole | 3\n"
+        );
+    }
+
+    #[test]
+    fn test_json_format() {
+        let rows = json!([
+            { "goals": 27, "player_name": "Haaland, Erling", "gf_1h": null },
+            { "goals": 22, "player_name": "Palmer, Cole", "gf_1h": 3 },
+        ]);
+        let json: serde_json::Value =
+            serde_json::from_str(&render(OutputFormat::Json, &[mock_table()])).unwrap();
+        assert_eq!(json, rows);
+
+        let json: serde_json::Value = serde_json::from_str(&render(
+            OutputFormat::Json,
+            &[
+                mock_table().with_title("Home"),
+                mock_table().with_title("Away"),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(json, json!({ "home": rows, "away": rows }));
+    }
+}
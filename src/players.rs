@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::AddAssign;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::{Competitor, PlayerStats};
+
+// A player of a season, decoupled from the API response so it can carry
+// the clubs they played for, more than one if they moved mid season.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonPlayer {
+    pub id: String,
+    pub name: String,
+    // Team abbreviations, in the order the API lists the clubs
+    pub teams: Vec<String>,
+    pub statistics: PlayerStats,
+}
+
+impl SeasonPlayer {
+    // The clubs are in the order of the season's competitors, not the order
+    // the player played for them
+    pub fn team(&self) -> String {
+        self.teams.join("/")
+    }
+}
+
+// Players who moved mid season are listed by each of their clubs. Unless split
+// by team, they are merged by ID into a single player with the stats summed.
+pub fn season_players(teams: Vec<Competitor>, split_by_team: bool) -> Vec<SeasonPlayer> {
+    let mut players: Vec<SeasonPlayer> = Vec::with_capacity(20 * 28);
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for team in teams {
+        for player in team.players {
+            match positions.get(&player.id) {
+                Some(&i) if !split_by_team => {
+                    players[i].teams.push(team.abbreviation.clone());
+                    players[i].statistics += &player.statistics;
+                }
+                _ => {
+                    positions.insert(player.id.clone(), players.len());
+                    players.push(SeasonPlayer {
+                        id: player.id,
+                        name: player.name,
+                        teams: vec![team.abbreviation.clone()],
+                        statistics: player.statistics,
+                    });
+                }
+            }
+        }
+    }
+
+    players
+}
+
+impl AddAssign<&PlayerStats> for PlayerStats {
+    fn add_assign(&mut self, other: &PlayerStats) {
+        self.assists += other.assists;
+        self.goals_scored += other.goals_scored;
+        self.matches_played += other.matches_played;
+        self.goals_by_head += other.goals_by_head;
+        self.goals_by_penalty += other.goals_by_penalty;
+        self.penalties_missed += other.penalties_missed;
+        self.own_goals += other.own_goals;
+        self.shots_on_target += other.shots_on_target;
+        self.shots_off_target += other.shots_off_target;
+        self.shots_blocked += other.shots_blocked;
+        self.corner_kicks += other.corner_kicks;
+        self.offsides += other.offsides;
+        self.yellow_cards += other.yellow_cards;
+        self.yellow_red_cards += other.yellow_red_cards;
+        self.red_cards += other.red_cards;
+        self.substituted_in += other.substituted_in;
+        self.substituted_out += other.substituted_out;
+    }
+}
+
+// Matches the player ID, or every word of the query against the name,
+// so "cole palmer" finds "Palmer, Cole"
+pub fn find_player<'a>(
+    players: &'a [SeasonPlayer],
+    query: &str,
+) -> Result<&'a SeasonPlayer, Error> {
+    if let Some(player) = players.iter().find(|p| p.id == query) {
+        return Ok(player);
+    }
+
+    let query = query.to_lowercase();
+    let words: Vec<&str> = query
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut matches: Vec<&SeasonPlayer> = players
+        .iter()
+        .filter(|p| {
+            let name = p.name.to_lowercase();
+            words.iter().all(|w| name.contains(w))
+        })
+        .collect();
+    // The same player may be listed more than once, by other clubs or seasons
+    let mut seen = HashSet::new();
+    matches.retain(|p| seen.insert(p.id.as_str()));
+
+    match matches.as_slice() {
+        [] => Err(Error::PlayerNotFound(query)),
+        [player] => Ok(player),
+        _ => match matches.iter().find(|p| p.name.to_lowercase() == query) {
+            Some(player) => Ok(player),
+            None => Err(Error::AmbiguousPlayer {
+                query,
+                matches: matches.iter().map(|p| p.name.clone()).collect(),
+            }),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::players::{find_player, season_players, SeasonPlayer};
+    use crate::types::{Competitor, Player, PlayerStats};
+
+    fn mock_players(names: &[(&str, &str)]) -> Vec<SeasonPlayer> {
+        names
+            .iter()
+            .map(|(id, name)| SeasonPlayer {
+                id: id.to_string(),
+                name: name.to_string(),
+                teams: vec![],
+                statistics: Default::default(),
+            })
+            .collect()
+    }
+
+    fn mock_team(abbreviation: &str, players: &[(&str, usize)]) -> Competitor {
+        Competitor {
+            id: Default::default(),
+            name: Default::default(),
+            abbreviation: abbreviation.to_string(),
+            statistics: Default::default(),
+            players: players
+                .iter()
+                .map(|(id, goals)| Player {
+                    id: id.to_string(),
+                    name: format!("Player {id}"),
+                    statistics: PlayerStats {
+                        goals_scored: *goals,
+                        matches_played: 10,
+                        ..Default::default()
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn mock_transfer() -> Vec<Competitor> {
+        vec![
+            mock_team("CHE", &[("1", 3), ("2", 8)]),
+            mock_team("NEW", &[("3", 12), ("1", 5)]),
+        ]
+    }
+
+    #[test]
+    fn test_season_players_merges_transfers() {
+        let players = season_players(mock_transfer(), false);
+
+        assert_eq!(
+            players
+                .iter()
+                .map(|p| (p.id.as_str(), p.team(), p.statistics.goals_scored))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1", "CHE/NEW".to_string(), 8),
+                ("2", "CHE".to_string(), 8),
+                ("3", "NEW".to_string(), 12)
+            ]
+        );
+        assert_eq!(players[0].statistics.matches_played, 20);
+    }
+
+    #[test]
+    fn test_season_players_split_by_team() {
+        let players = season_players(mock_transfer(), true);
+
+        assert_eq!(
+            players
+                .iter()
+                .map(|p| (p.id.as_str(), p.team(), p.statistics.goals_scored))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1", "CHE".to_string(), 3),
+                ("2", "CHE".to_string(), 8),
+                ("3", "NEW".to_string(), 12),
+                ("1", "NEW".to_string(), 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_player() {
+        let players = mock_players(&[
+            ("sr:player:1", "Palmer, Cole"),
+            ("sr:player:2", "Saka, Bukayo"),
+            ("sr:player:3", "Palmer, Kasey"),
+            ("sr:player:2", "Saka, Bukayo"),
+        ]);
+
+        assert_eq!(
+            find_player(&players, "sr:player:2").unwrap().name,
+            "Saka, Bukayo"
+        );
+        assert_eq!(find_player(&players, "saka").unwrap().id, "sr:player:2");
+        assert_eq!(
+            find_player(&players, "Cole Palmer").unwrap().id,
+            "sr:player:1"
+        );
+        assert!(matches!(
+            find_player(&players, "palmer"),
+            Err(Error::AmbiguousPlayer { .. })
+        ));
+        assert!(matches!(
+            find_player(&players, "haaland"),
+            Err(Error::PlayerNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_stats() {
+        let mut total = PlayerStats {
+            goals_scored: 10,
+            assists: 2,
+            ..Default::default()
+        };
+        total += &PlayerStats {
+            goals_scored: 4,
+            yellow_cards: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            total,
+            PlayerStats {
+                goals_scored: 14,
+                assists: 2,
+                yellow_cards: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use crate::config::Config;
+use crate::error::{Error, IOError, JSONError};
+
+// Kept by clearing the cache, as the calls were made all the same
+pub const LEDGER_FILE: &str = "quota.json";
+
+// Calls made to the API by month and endpoint, as trial keys have a monthly cap:
+//
+// .matchday/quota.json
+// {"2026-10": {"competitor_stats": 20, "seasons": 1}}
+//
+// The file is read again before each call is added, so processes sharing
+// the cache dir one after the other don't lose each other's calls.
+pub struct Ledger {
+    file: PathBuf,
+    budget: Option<u64>,
+    lock: Mutex<()>,
+}
+
+type Months = BTreeMap<String, BTreeMap<String, u64>>;
+
+// The calls of a month against the budget
+#[derive(Clone, Debug, PartialEq)]
+pub struct Usage {
+    pub month: String,
+    pub endpoints: BTreeMap<String, u64>,
+    pub budget: Option<u64>,
+}
+
+impl Ledger {
+    pub fn new(cache_dir: &Path, budget: Option<u64>) -> Self {
+        Self {
+            file: cache_dir.join(LEDGER_FILE),
+            budget,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.cache_dir, config.quota_budget)
+    }
+
+    pub fn record(&self, endpoint: &str) -> Result<(), Error> {
+        let _lock = self.lock.lock().unwrap();
+        let mut months = self.read()?;
+        *months
+            .entry(current_month())
+            .or_default()
+            .entry(endpoint.to_string())
+            .or_default() += 1;
+        self.write(&months)
+    }
+
+    // Of the current month, in UTC
+    pub fn usage(&self) -> Result<Usage, Error> {
+        let _lock = self.lock.lock().unwrap();
+        let month = current_month();
+        let endpoints = self.read()?.remove(&month).unwrap_or_default();
+        Ok(Usage {
+            month,
+            endpoints,
+            budget: self.budget,
+        })
+    }
+
+    fn read(&self) -> Result<Months, Error> {
+        if !self.file.exists() {
+            return Ok(Months::new());
+        }
+        let raw = fs::read_to_string(&self.file)
+            .map_err(|io_err| IOError::new(self.file.clone(), io_err))?;
+        Ok(serde_json::from_str(&raw).map_err(|e| JSONError::new(Some(self.file.clone()), e))?)
+    }
+
+    // Into a temporary file first, so an interrupted write doesn't lose the ledger
+    fn write(&self, months: &Months) -> Result<(), Error> {
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir).map_err(|io_err| IOError::new(dir.to_path_buf(), io_err))?;
+        }
+        let tmp_file = self.file.with_extension("json.tmp");
+        fs::write(
+            &tmp_file,
+            serde_json::to_string(months).map_err(|e| JSONError::new(None, e))?,
+        )
+        .map_err(|io_err| IOError::new(tmp_file.clone(), io_err))?;
+        fs::rename(&tmp_file, &self.file)
+            .map_err(|io_err| IOError::new(self.file.clone(), io_err))?;
+        Ok(())
+    }
+}
+
+impl Usage {
+    pub fn calls(&self) -> u64 {
+        self.endpoints.values().sum()
+    }
+
+    // Unlimited without a budget
+    pub fn remaining(&self) -> Option<u64> {
+        self.budget
+            .map(|budget| budget.saturating_sub(self.calls()))
+    }
+
+    // Errors if the calls don't fit in what's left of the budget
+    pub fn check(&self, needed: u64) -> Result<(), Error> {
+        match self.remaining() {
+            Some(remaining) if needed > remaining => {
+                Err(Error::QuotaExceeded { needed, remaining })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn current_month() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::quota::{Ledger, LEDGER_FILE};
+
+    #[test]
+    fn test_ledger() {
+        let cache_dir = std::env::temp_dir().join("matchday-quota-ledger");
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let ledger = Ledger::new(&cache_dir, Some(5));
+        assert_eq!(ledger.usage().unwrap().calls(), 0);
+        ledger.record("seasons").unwrap();
+        ledger.record("competitor_stats").unwrap();
+        ledger.record("competitor_stats").unwrap();
+
+        // read again from the file
+        let usage = Ledger::new(&cache_dir, Some(5)).usage().unwrap();
+        assert_eq!(usage.calls(), 3);
+        assert_eq!(usage.endpoints["competitor_stats"], 2);
+        assert_eq!(usage.remaining(), Some(2));
+        assert!(usage.check(2).is_ok());
+        assert_eq!(
+            usage.check(3).unwrap_err().to_string(),
+            "needs 3 API calls, but only 2 are left in this month's budget"
+        );
+
+        let unlimited = Ledger::new(&cache_dir, None).usage().unwrap();
+        assert_eq!(unlimited.remaining(), None);
+        assert!(unlimited.check(1000).is_ok());
+
+        assert!(cache_dir.join(LEDGER_FILE).exists());
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::error::Error;
+use crate::types::{CompetitionSeasons, Season};
+
+// Seasons can be referred to by their ID or year, such as "sr:season:105353" or "23/24"
+pub fn find_season<'a>(seasons: &'a CompetitionSeasons, query: &str) -> Result<&'a Season, Error> {
+    seasons
+        .seasons
+        .iter()
+        .find(|season| season.id == query || season.year == query)
+        .ok_or_else(|| Error::SeasonNotFound(query.to_string()))
+}
+
+// Ranges such as "21/22..23/24" are inclusive, a single season is also accepted
+pub fn find_seasons(seasons: &CompetitionSeasons, range: &str) -> Result<Vec<Season>, Error> {
+    let (first, last) = match range.split_once("..") {
+        Some((first, last)) => (find_season(seasons, first)?, find_season(seasons, last)?),
+        None => {
+            let season = find_season(seasons, range)?;
+            (season, season)
+        }
+    };
+
+    let mut found: Vec<Season> = seasons
+        .seasons
+        .iter()
+        .filter(|season| season.start_date >= first.start_date)
+        .filter(|season| season.start_date <= last.start_date)
+        .cloned()
+        .collect();
+    found.sort_by_key(|season| season.start_date);
+
+    Ok(found)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::seasons::find_seasons;
+    use crate::types::{CompetitionSeasons, Season};
+
+    fn mock_seasons(years: &[(&str, &str)]) -> CompetitionSeasons {
+        CompetitionSeasons {
+            seasons: years
+                .iter()
+                .map(|(id, year)| {
+                    let start_year: i32 = year[0..2].parse().unwrap();
+                    Season {
+                        id: id.to_string(),
+                        name: format!("Premier League {year}"),
+                        start_date: format!("20{start_year}-08-01").parse().unwrap(),
+                        end_date: format!("20{}-05-31", start_year + 1).parse().unwrap(),
+                        year: year.to_string(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_seasons() {
+        let seasons = mock_seasons(&[
+            ("sr:season:105353", "23/24"),
+            ("sr:season:77924", "21/22"),
+            ("sr:season:93741", "22/23"),
+            ("sr:season:118689", "24/25"),
+        ]);
+
+        let ids = |range| -> Vec<String> {
+            find_seasons(&seasons, range)
+                .unwrap()
+                .into_iter()
+                .map(|season| season.id)
+                .collect()
+        };
+
+        assert_eq!(
+            ids("21/22..23/24"),
+            vec!["sr:season:77924", "sr:season:93741", "sr:season:105353"]
+        );
+        assert_eq!(ids("sr:season:118689"), vec!["sr:season:118689"]);
+        assert_eq!(
+            ids("22/23..sr:season:105353"),
+            vec!["sr:season:93741", "sr:season:105353"]
+        );
+        assert!(ids("23/24..21/22").is_empty());
+        assert!(matches!(
+            find_seasons(&seasons, "19/20..23/24"),
+            Err(Error::SeasonNotFound(year)) if year == "19/20"
+        ));
+    }
+}
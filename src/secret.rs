@@ -0,0 +1,51 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+// Keeps secrets such as the API key out of debug output, errors and logs,
+// the value can only be read through `expose`
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.into())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(********)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::secret::Secret;
+
+    #[test]
+    fn test_redacted() {
+        let secret = Secret::new("asdf1234");
+
+        assert_eq!(format!("{secret:?}"), "Secret(********)");
+        assert_eq!(format!("{:?}", Some(&secret)), "Some(Secret(********))");
+        assert_eq!(secret.expose(), "asdf1234");
+    }
+}
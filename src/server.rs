@@ -0,0 +1,291 @@
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::cached_client::CachedClient;
+use crate::client::Client;
+use crate::error::Error;
+use crate::matchday::Matchday;
+use crate::players::{find_player, SeasonPlayer};
+use crate::types::{Competitor, Season, StandingRow, StandingType};
+
+// Serves the leaderboards as JSON over HTTP. Seasons are given by ID or by
+// year, with the slash escaped ("23%2F24"), and the endpoints without one
+// use the configured season:
+//
+// GET /health
+// GET /metrics
+// GET /seasons
+// GET /seasons/{season}/top/{goals,assists,players}?limit=
+// GET /seasons/{season}/teams
+// GET /seasons/{season}/standings
+// GET /teams
+// GET /players/{id}
+#[derive(Clone, Debug)]
+pub struct ServerOptions {
+    pub season: String,
+    // Players returned by the top endpoints without a limit
+    pub limit: usize,
+    // How often the season data is fetched again in the background
+    pub refresh: Option<Duration>,
+}
+
+struct ServerState<C> {
+    matchday: Matchday<CachedClient<C>>,
+    options: ServerOptions,
+}
+
+// Stops serving once dropped
+pub struct Server {
+    serving: Serving,
+}
+
+impl Server {
+    // Listens on a random local port
+    pub async fn start<C: Client + 'static>(
+        matchday: Matchday<CachedClient<C>>,
+        options: ServerOptions,
+    ) -> io::Result<Self> {
+        Self::bind(([127, 0, 0, 1], 0).into(), matchday, options).await
+    }
+
+    pub async fn bind<C: Client + 'static>(
+        addr: SocketAddr,
+        matchday: Matchday<CachedClient<C>>,
+        options: ServerOptions,
+    ) -> io::Result<Self> {
+        let state = Arc::new(ServerState { matchday, options });
+        let app = Router::new()
+            .route("/health", get(health))
+            .route("/metrics", get(metrics::<C>))
+            .route("/seasons", get(seasons::<C>))
+            .route("/seasons/:season/top/:stat", get(top::<C>))
+            .route("/seasons/:season/teams", get(season_teams::<C>))
+            .route("/seasons/:season/standings", get(standings::<C>))
+            .route("/teams", get(teams::<C>))
+            .route("/players/:id", get(player::<C>))
+            .with_state(state.clone());
+        let mut serving = Serving::bind(addr, app).await?;
+        if let Some(every) = state.options.refresh {
+            serving.spawn(refresh(state, every));
+        }
+
+        Ok(Self { serving })
+    }
+
+    pub fn url(&self) -> String {
+        self.serving.url()
+    }
+
+    pub async fn wait(&mut self) {
+        self.serving.wait().await;
+    }
+}
+
+// An app served in a task, and the tasks running alongside it, which are all
+// stopped once dropped. Shared with the mock server.
+pub(crate) struct Serving {
+    addr: SocketAddr,
+    handle: JoinHandle<()>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Serving {
+    pub(crate) async fn bind(addr: SocketAddr, app: Router) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Ok(Self {
+            addr,
+            handle,
+            tasks: Vec::new(),
+        })
+    }
+
+    pub(crate) fn spawn(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        self.tasks.push(tokio::spawn(task));
+    }
+
+    pub(crate) fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // Serves until the task is stopped
+    pub(crate) async fn wait(&mut self) {
+        let _ = (&mut self.handle).await;
+    }
+}
+
+impl Drop for Serving {
+    fn drop(&mut self) {
+        self.handle.abort();
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+// Only the data older than the cache TTL, or than the interval without one,
+// is fetched again, and only if it fits in the quota budget. The first
+// refresh also fills in what isn't cached yet, failures are retried on the
+// next one.
+async fn refresh<C: Client>(state: Arc<ServerState<C>>, every: Duration) {
+    let season = &state.options.season;
+    let max_age = state.matchday.cache_ttl().unwrap_or(every);
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await;
+        let calls = match state.matchday.stale_calls(season, max_age).await {
+            Ok(calls) => calls,
+            Err(e) => {
+                warn!(error = %e, "failed to refresh season data");
+                continue;
+            }
+        };
+        if calls == 0 {
+            continue;
+        }
+        let within_budget = state
+            .matchday
+            .quota()
+            .and_then(|usage| usage.map_or(Ok(()), |usage| usage.check(calls)));
+        if let Err(e) = within_budget {
+            warn!(error = %e, "skipped refreshing season data");
+            continue;
+        }
+        match state.matchday.refresh_stale(season, max_age).await {
+            Ok(()) => info!(season, calls, "refreshed season data"),
+            Err(e) => warn!(error = %e, "failed to refresh season data"),
+        }
+    }
+}
+
+// Lookups that found nothing are the client's fault, anything else is upstream's
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::SeasonNotFound(_) | Error::PlayerNotFound(_) => {
+                Self(StatusCode::NOT_FOUND, e.to_string())
+            }
+            Error::AmbiguousPlayer { .. } => Self(StatusCode::BAD_REQUEST, e.to_string()),
+            _ => {
+                // The details may have the upstream URL, they're only logged
+                error!(error = %e, "request failed");
+                Self(StatusCode::BAD_GATEWAY, "failed to fetch the data".into())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Deserialize)]
+struct TopQuery {
+    limit: Option<usize>,
+}
+
+// A player with their rank in the leaderboard
+#[derive(Serialize)]
+struct Ranked {
+    rank: usize,
+    #[serde(flatten)]
+    player: SeasonPlayer,
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+// Prometheus text format
+async fn metrics<C: Client>(State(state): State<Arc<ServerState<C>>>) -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.matchday.metrics().render(),
+    )
+}
+
+async fn seasons<C: Client>(State(state): State<Arc<ServerState<C>>>) -> ApiResult<Vec<Season>> {
+    Ok(Json(state.matchday.seasons().await?))
+}
+
+async fn top<C: Client>(
+    State(state): State<Arc<ServerState<C>>>,
+    Path((season, stat)): Path<(String, String)>,
+    Query(query): Query<TopQuery>,
+) -> ApiResult<Vec<Ranked>> {
+    let limit = query.limit.unwrap_or(state.options.limit);
+    let players = match stat.as_str() {
+        "goals" => state.matchday.top_scorers(&season, limit).await?,
+        "assists" => state.matchday.top_assists(&season, limit).await?,
+        "players" => state.matchday.top_players(&season, limit).await?,
+        _ => {
+            return Err(ApiError(
+                StatusCode::NOT_FOUND,
+                format!("no leaderboard for \"{stat}\", use goals, assists or players"),
+            ))
+        }
+    };
+
+    Ok(Json(
+        players
+            .into_iter()
+            .enumerate()
+            .map(|(i, player)| Ranked {
+                rank: i + 1,
+                player,
+            })
+            .collect(),
+    ))
+}
+
+async fn season_teams<C: Client>(
+    State(state): State<Arc<ServerState<C>>>,
+    Path(season): Path<String>,
+) -> ApiResult<Vec<Competitor>> {
+    Ok(Json(state.matchday.teams(&season).await?))
+}
+
+async fn standings<C: Client>(
+    State(state): State<Arc<ServerState<C>>>,
+    Path(season): Path<String>,
+) -> ApiResult<Vec<StandingRow>> {
+    let standings = state.matchday.standings(&season).await?;
+    Ok(Json(standings.rows(StandingType::Total).collect()))
+}
+
+async fn teams<C: Client>(State(state): State<Arc<ServerState<C>>>) -> ApiResult<Vec<Competitor>> {
+    Ok(Json(state.matchday.teams(&state.options.season).await?))
+}
+
+// By ID, or by name as in the commands
+async fn player<C: Client>(
+    State(state): State<Arc<ServerState<C>>>,
+    Path(id): Path<String>,
+) -> ApiResult<SeasonPlayer> {
+    let players = state.matchday.players(&state.options.season, false).await?;
+    Ok(Json(find_player(&players, &id)?.clone()))
+}
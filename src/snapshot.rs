@@ -0,0 +1,261 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, IOError, JSONError};
+use crate::players::SeasonPlayer;
+use crate::top_players::Leaderboard;
+use crate::watch::{self, LiveEntry, LiveLeaderboard};
+
+// Kept by clearing the cache, they can't be fetched again
+pub const SNAPSHOTS_FOLDER: &str = "snapshots";
+
+// The players of a season at some point, with their stats, so the rankings
+// of any leaderboard can be compared later on
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub name: String,
+    pub season: String,
+    pub taken_at: DateTime<Utc>,
+    pub players: Vec<SeasonPlayer>,
+}
+
+// Named snapshots in the cache dir:
+//
+// .matchday/snapshots/<name>.json
+pub struct Snapshots {
+    dir: PathBuf,
+}
+
+impl Snapshots {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            dir: cache_dir.join(SNAPSHOTS_FOLDER),
+        }
+    }
+
+    pub fn exists(&self, name: &str) -> Result<bool, Error> {
+        Ok(self.file(name)?.exists())
+    }
+
+    // Refuses to replace a snapshot with the same name, unless overwritten
+    pub fn save(&self, snapshot: &Snapshot, overwrite: bool) -> Result<(), Error> {
+        let file = self.file(&snapshot.name)?;
+        if file.exists() && !overwrite {
+            return Err(Error::SnapshotExists(snapshot.name.clone()));
+        }
+        fs::create_dir_all(&self.dir).map_err(|io_err| IOError::new(self.dir.clone(), io_err))?;
+        fs::write(
+            &file,
+            serde_json::to_string(snapshot).map_err(|e| JSONError::new(None, e))?,
+        )
+        .map_err(|io_err| IOError::new(file, io_err))?;
+        Ok(())
+    }
+
+    pub fn load(&self, name: &str) -> Result<Snapshot, Error> {
+        let file = self.file(name)?;
+        if !file.exists() {
+            return Err(Error::SnapshotNotFound(name.to_string()));
+        }
+        Self::read(&file)
+    }
+
+    // From the oldest to the newest
+    pub fn list(&self) -> Result<Vec<Snapshot>, Error> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let entries =
+            fs::read_dir(&self.dir).map_err(|io_err| IOError::new(self.dir.clone(), io_err))?;
+        let mut snapshots = Vec::new();
+        for entry in entries {
+            let file = entry
+                .map_err(|io_err| IOError::new(self.dir.clone(), io_err))?
+                .path();
+            if file
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                snapshots.push(Self::read(&file)?);
+            }
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.taken_at);
+        Ok(snapshots)
+    }
+
+    fn read(file: &Path) -> Result<Snapshot, Error> {
+        let raw =
+            fs::read_to_string(file).map_err(|io_err| IOError::new(file.to_path_buf(), io_err))?;
+        Ok(serde_json::from_str(&raw).map_err(|e| JSONError::new(Some(file.to_path_buf()), e))?)
+    }
+
+    // Names end up in the file name, so they can't have slashes or start with a dot
+    fn file(&self, name: &str) -> Result<PathBuf, Error> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(Error::InvalidSnapshotName(name.to_string()));
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+}
+
+// How a leaderboard changed from one snapshot to another. Players are ranked
+// in the newer one, marked as new if they weren't ranked in the older one,
+// and the dropouts are the ones who left it, with their former rank. Only
+// snapshots of the same season are compared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotDiff {
+    pub entries: Vec<LiveEntry>,
+    pub dropouts: Vec<(usize, SeasonPlayer)>,
+}
+
+impl SnapshotDiff {
+    pub fn new(
+        from: &Snapshot,
+        to: &Snapshot,
+        leaderboard: Leaderboard,
+        limit: usize,
+    ) -> Result<Self, Error> {
+        if from.season != to.season {
+            return Err(Error::SnapshotSeasonMismatch {
+                from: from.name.clone(),
+                to: to.name.clone(),
+                from_season: from.season.clone(),
+                to_season: to.season.clone(),
+            });
+        }
+        let ranked = leaderboard.rank(from.players.clone(), limit);
+        let mut live = LiveLeaderboard::new();
+        live.update(&from.players, ranked.clone());
+        let entries = live.update(&to.players, leaderboard.rank(to.players.clone(), limit));
+
+        let dropouts = ranked
+            .into_iter()
+            .enumerate()
+            .filter(|(_, player)| {
+                !entries
+                    .iter()
+                    .any(|entry| watch::key(&entry.player) == watch::key(player))
+            })
+            .map(|(i, player)| (i + 1, player))
+            .collect();
+
+        Ok(Self { entries, dropouts })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use chrono::{TimeZone, Utc};
+
+    use crate::error::Error;
+    use crate::players::SeasonPlayer;
+    use crate::snapshot::{Snapshot, SnapshotDiff, Snapshots};
+    use crate::top_players::Leaderboard;
+    use crate::types::PlayerStats;
+    use crate::watch::Movement;
+
+    fn mock_snapshot(name: &str, day: u32, goals: &[(&str, usize)]) -> Snapshot {
+        Snapshot {
+            name: name.into(),
+            season: "sr:season:105353".into(),
+            taken_at: Utc.with_ymd_and_hms(2023, 10, day, 12, 0, 0).unwrap(),
+            players: goals
+                .iter()
+                .map(|(id, goals)| SeasonPlayer {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    teams: vec!["CHE".into()],
+                    statistics: PlayerStats {
+                        goals_scored: *goals,
+                        ..Default::default()
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let cache_dir = std::env::temp_dir().join("matchday-snapshots");
+        let _ = fs::remove_dir_all(&cache_dir);
+        let snapshots = Snapshots::new(&cache_dir);
+
+        let gw11 = mock_snapshot("gw11", 8, &[("palmer", 8)]);
+        let gw10 = mock_snapshot("gw10", 1, &[("palmer", 7)]);
+        snapshots.save(&gw11, false).unwrap();
+        snapshots.save(&gw10, false).unwrap();
+        assert_eq!(snapshots.load("gw10").unwrap(), gw10);
+        assert_eq!(snapshots.list().unwrap(), [gw10.clone(), gw11]);
+
+        assert!(matches!(
+            snapshots.save(&gw10, false),
+            Err(Error::SnapshotExists(_))
+        ));
+        assert!(snapshots.save(&gw10, true).is_ok());
+        assert!(matches!(
+            snapshots.load("gw12"),
+            Err(Error::SnapshotNotFound(_))
+        ));
+        assert!(matches!(
+            snapshots.load("../config"),
+            Err(Error::InvalidSnapshotName(_))
+        ));
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn test_diff() {
+        let gw10 = mock_snapshot("gw10", 1, &[("haaland", 9), ("son", 8), ("palmer", 7)]);
+        let gw11 = mock_snapshot("gw11", 8, &[("haaland", 11), ("son", 8), ("palmer", 10)]);
+
+        let diff = SnapshotDiff::new(&gw10, &gw11, Leaderboard::Goals, 2).unwrap();
+        let entries: Vec<(usize, &str, Movement, usize)> = diff
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.rank,
+                    entry.player.id.as_str(),
+                    entry.movement,
+                    entry.new_goals,
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (1, "haaland", Movement::Same, 2),
+                (2, "palmer", Movement::New, 3)
+            ]
+        );
+        let dropouts: Vec<(usize, &str)> = diff
+            .dropouts
+            .iter()
+            .map(|(rank, player)| (*rank, player.id.as_str()))
+            .collect();
+        assert_eq!(dropouts, [(2, "son")]);
+    }
+
+    #[test]
+    fn test_diff_of_different_seasons() {
+        let gw10 = mock_snapshot("gw10", 1, &[("haaland", 9)]);
+        let mut next_season = mock_snapshot("next", 8, &[("haaland", 2)]);
+        next_season.season = "sr:season:118689".into();
+
+        assert!(matches!(
+            SnapshotDiff::new(&gw10, &next_season, Leaderboard::Goals, 2),
+            Err(Error::SnapshotSeasonMismatch { .. })
+        ));
+    }
+}
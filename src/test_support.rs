@@ -0,0 +1,55 @@
+// Factories shared by the unit tests
+use crate::history::SeasonPlayers;
+use crate::players::SeasonPlayer;
+use crate::types::{Competitor, Player, PlayerStats, Season, TeamStats};
+
+pub fn mock_season(id: &str, year: &str) -> Season {
+    Season {
+        id: id.into(),
+        name: format!("Premier League {year}"),
+        start_date: "2023-08-11".parse().unwrap(),
+        end_date: "2024-05-19".parse().unwrap(),
+        year: year.into(),
+    }
+}
+
+// players as (id, name, goals)
+pub fn mock_team(id: &str, players: &[(&str, &str, usize)]) -> Competitor {
+    Competitor {
+        id: id.into(),
+        name: id.into(),
+        abbreviation: id.to_uppercase(),
+        statistics: TeamStats::default(),
+        players: players
+            .iter()
+            .map(|(id, name, goals)| Player {
+                id: id.to_string(),
+                name: name.to_string(),
+                statistics: mock_stats(*goals),
+            })
+            .collect(),
+    }
+}
+
+// players as (team, id, goals)
+pub fn mock_season_players(id: &str, year: &str, players: &[(&str, &str, usize)]) -> SeasonPlayers {
+    SeasonPlayers {
+        season: mock_season(id, year),
+        players: players
+            .iter()
+            .map(|(team, id, goals)| SeasonPlayer {
+                id: id.to_string(),
+                name: format!("Player {id}"),
+                teams: vec![team.to_string()],
+                statistics: mock_stats(*goals),
+            })
+            .collect(),
+    }
+}
+
+fn mock_stats(goals: usize) -> PlayerStats {
+    PlayerStats {
+        goals_scored: goals,
+        ..Default::default()
+    }
+}
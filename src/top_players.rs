@@ -1,44 +1,68 @@
-use crate::types::Player;
+use crate::players::SeasonPlayer;
 
-pub struct TopPlayers(Vec<Player>);
+pub struct TopPlayers(Vec<SeasonPlayer>, usize);
 
 impl TopPlayers {
-    pub fn new(players: Vec<Player>) -> Self {
-        Self(players)
+    pub fn new(players: Vec<SeasonPlayer>, limit: usize) -> Self {
+        Self(players, limit)
     }
-    pub fn by_assists(mut self) -> impl Iterator<Item = Player> {
+    pub fn by_assists(mut self) -> impl Iterator<Item = SeasonPlayer> {
         self.0.sort_by_key(|p| p.statistics.assists);
-        self.0.into_iter().rev().take(10)
+        self.0.into_iter().rev().take(self.1)
     }
-    pub fn by_goals(mut self) -> impl Iterator<Item = Player> {
+    pub fn by_goals(mut self) -> impl Iterator<Item = SeasonPlayer> {
         self.0.sort_by_key(|p| p.statistics.goals_scored);
-        self.0.into_iter().rev().take(10)
+        self.0.into_iter().rev().take(self.1)
     }
-    pub fn by_both(mut self) -> impl Iterator<Item = Player> {
+    pub fn by_both(mut self) -> impl Iterator<Item = SeasonPlayer> {
         self.0
             .sort_unstable_by_key(|p| (p.statistics.goals_scored, p.statistics.assists));
-        self.0.into_iter().rev().take(10)
+        self.0.into_iter().rev().take(self.1)
+    }
+}
+
+// The leaderboards of the top commands, players is ordered by goals then assists
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Leaderboard {
+    Goals,
+    Assists,
+    #[default]
+    Players,
+}
+
+impl Leaderboard {
+    pub fn rank(self, players: Vec<SeasonPlayer>, limit: usize) -> Vec<SeasonPlayer> {
+        let top_players = TopPlayers::new(players, limit);
+        match self {
+            Leaderboard::Goals => top_players.by_goals().collect(),
+            Leaderboard::Assists => top_players.by_assists().collect(),
+            Leaderboard::Players => top_players.by_both().collect(),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::players::SeasonPlayer;
     use crate::top_players::TopPlayers;
-    use crate::types::{Player, PlayerStats};
+    use crate::types::PlayerStats;
 
     type Goals = usize;
     type Assists = usize;
     type Stat = (Goals, Assists);
 
-    fn mock_players(stats: &Vec<Stat>) -> Vec<Player> {
+    fn mock_players(stats: &[Stat]) -> Vec<SeasonPlayer> {
         stats
             .iter()
-            .map(|(goals, assists)| Player {
+            .map(|(goals, assists)| SeasonPlayer {
                 id: Default::default(),
                 name: Default::default(),
+                teams: Default::default(),
                 statistics: PlayerStats {
                     assists: *assists,
                     goals_scored: *goals,
+                    ..Default::default()
                 },
             })
             .collect()
@@ -61,7 +85,7 @@ mod test {
             (1, 0),
         ];
         let players = mock_players(&stats);
-        let top = TopPlayers::new(players);
+        let top = TopPlayers::new(players, 10);
         let expected = vec![
             (22, 0),
             (12, 0),
@@ -99,7 +123,7 @@ mod test {
             (0, 1),
         ];
         let players = mock_players(&stats);
-        let top = TopPlayers::new(players);
+        let top = TopPlayers::new(players, 10);
         let expected = vec![
             (0, 22),
             (0, 12),
@@ -137,7 +161,7 @@ mod test {
             (2, 1),
         ];
         let players = mock_players(&stats);
-        let top = TopPlayers::new(players);
+        let top = TopPlayers::new(players, 10);
         let expected = vec![
             (33, 5),
             (11, 4),
@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+
+use crate::error::Error;
+use crate::types::{Competitor, TeamStats};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TeamColumn {
+    Possession,
+    GoalsScored,
+    GoalsScoredFirstHalf,
+    GoalsScoredSecondHalf,
+    GoalsConceded,
+    GoalsConcededFirstHalf,
+    GoalsConcededSecondHalf,
+    GoalsByHead,
+    GoalsByFoot,
+    Shots,
+    ShotsOnTarget,
+    Corners,
+    Cards,
+}
+
+impl TeamColumn {
+    fn value(&self, stats: &TeamStats) -> f64 {
+        match self {
+            TeamColumn::Possession => stats.average_ball_possession,
+            TeamColumn::GoalsScored => stats.goals_scored as f64,
+            TeamColumn::GoalsScoredFirstHalf => stats.goals_scored_first_half as f64,
+            TeamColumn::GoalsScoredSecondHalf => stats.goals_scored_second_half as f64,
+            TeamColumn::GoalsConceded => stats.goals_conceded as f64,
+            TeamColumn::GoalsConcededFirstHalf => stats.goals_conceded_first_half as f64,
+            TeamColumn::GoalsConcededSecondHalf => stats.goals_conceded_second_half as f64,
+            TeamColumn::GoalsByHead => stats.goals_by_head as f64,
+            TeamColumn::GoalsByFoot => stats.goals_by_foot as f64,
+            TeamColumn::Shots => stats.shots_total as f64,
+            TeamColumn::ShotsOnTarget => stats.shots_on_target as f64,
+            TeamColumn::Corners => stats.corner_kicks as f64,
+            TeamColumn::Cards => stats.cards_given as f64,
+        }
+    }
+
+    // Conceding less is better, so the best defenses come first
+    fn ascending(&self) -> bool {
+        matches!(
+            self,
+            TeamColumn::GoalsConceded
+                | TeamColumn::GoalsConcededFirstHalf
+                | TeamColumn::GoalsConcededSecondHalf
+        )
+    }
+}
+
+pub struct TopTeams(Vec<Competitor>);
+
+impl TopTeams {
+    pub fn new(teams: Vec<Competitor>) -> Self {
+        Self(teams)
+    }
+    // Only the teams whose name has the query, or with it as abbreviation,
+    // ignoring case like the fixtures filter
+    pub fn named(mut self, query: &str) -> Result<Self, Error> {
+        let lowercase = query.to_lowercase();
+        self.0.retain(|team| {
+            team.name.to_lowercase().contains(&lowercase)
+                || team.abbreviation.to_lowercase() == lowercase
+        });
+        if self.0.is_empty() {
+            return Err(Error::TeamNotFound(query.to_string()));
+        }
+        Ok(self)
+    }
+    pub fn sort_by(mut self, column: TeamColumn) -> impl Iterator<Item = Competitor> {
+        self.0.sort_by(|a, b| {
+            let ordering = column
+                .value(&a.statistics)
+                .partial_cmp(&column.value(&b.statistics))
+                .unwrap_or(Ordering::Equal);
+            if column.ascending() {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Error;
+    use crate::top_teams::{TeamColumn, TopTeams};
+    use crate::types::{Competitor, TeamStats};
+
+    fn mock_teams(stats: &[(&str, TeamStats)]) -> Vec<Competitor> {
+        stats
+            .iter()
+            .map(|(abbreviation, statistics)| Competitor {
+                id: Default::default(),
+                name: Default::default(),
+                abbreviation: abbreviation.to_string(),
+                statistics: statistics.clone(),
+                players: vec![],
+            })
+            .collect()
+    }
+
+    fn abbreviations(teams: impl Iterator<Item = Competitor>) -> Vec<String> {
+        teams.map(|t| t.abbreviation).collect()
+    }
+
+    #[test]
+    fn test_best_attack() {
+        let teams = mock_teams(&[
+            (
+                "ARS",
+                TeamStats {
+                    goals_scored: 91,
+                    ..Default::default()
+                },
+            ),
+            (
+                "MCI",
+                TeamStats {
+                    goals_scored: 96,
+                    ..Default::default()
+                },
+            ),
+            (
+                "SHU",
+                TeamStats {
+                    goals_scored: 35,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        assert_eq!(
+            abbreviations(TopTeams::new(teams).sort_by(TeamColumn::GoalsScored)),
+            vec!["MCI", "ARS", "SHU"]
+        );
+    }
+
+    #[test]
+    fn test_best_defense() {
+        let teams = mock_teams(&[
+            (
+                "SHU",
+                TeamStats {
+                    goals_conceded: 104,
+                    ..Default::default()
+                },
+            ),
+            (
+                "ARS",
+                TeamStats {
+                    goals_conceded: 29,
+                    ..Default::default()
+                },
+            ),
+            (
+                "MCI",
+                TeamStats {
+                    goals_conceded: 34,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        assert_eq!(
+            abbreviations(TopTeams::new(teams).sort_by(TeamColumn::GoalsConceded)),
+            vec!["ARS", "MCI", "SHU"]
+        );
+    }
+
+    #[test]
+    fn test_possession() {
+        let teams = mock_teams(&[
+            (
+                "BUR",
+                TeamStats {
+                    average_ball_possession: 45.2,
+                    ..Default::default()
+                },
+            ),
+            (
+                "MCI",
+                TeamStats {
+                    average_ball_possession: 65.53,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        assert_eq!(
+            abbreviations(TopTeams::new(teams).sort_by(TeamColumn::Possession)),
+            vec!["MCI", "BUR"]
+        );
+    }
+
+    #[test]
+    fn test_named() {
+        let teams = mock_teams(&[("ARS", TeamStats::default()), ("MCI", TeamStats::default())]);
+        assert_eq!(
+            abbreviations(
+                TopTeams::new(teams.clone())
+                    .named("mci")
+                    .unwrap()
+                    .sort_by(TeamColumn::GoalsScored)
+            ),
+            vec!["MCI"]
+        );
+        assert!(matches!(
+            TopTeams::new(teams).named("LIV"),
+            Err(Error::TeamNotFound(_))
+        ));
+    }
+}
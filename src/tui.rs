@@ -0,0 +1,571 @@
+use std::cmp::Ordering;
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table as TableWidget, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::client::Client;
+use crate::cmd;
+use crate::compare::compare;
+use crate::error::Error;
+use crate::league_table::LeagueTable;
+use crate::matchday::Matchday;
+use crate::output::Table;
+use crate::players::SeasonPlayer;
+use crate::top_players::Leaderboard;
+use crate::top_teams::{TeamColumn, TopTeams};
+use crate::types::Competitor;
+
+const HELP: &str = "Tab/1-5 tabs · ↑↓ select · ←→ column · s sort · / search · q quit";
+
+// What the dashboard shows, loaded once before it starts
+pub struct Dashboard {
+    pub season: String,
+    pub players: Vec<SeasonPlayer>,
+    pub teams: Vec<Competitor>,
+    // Without the tab if they aren't cached while offline
+    pub standings: Option<LeagueTable>,
+}
+
+impl Dashboard {
+    pub async fn load<C: Client>(matchday: &Matchday<C>, season: &str) -> Result<Self, Error> {
+        let standings = match matchday.standings(season).await {
+            Ok(standings) => Some(standings),
+            Err(Error::NotCached(what)) => {
+                warn!("No cached {what}, leaving out the standings");
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        let (teams, players) = matchday.teams_and_players(season, false).await?;
+        Ok(Self {
+            season: season.to_string(),
+            players,
+            teams,
+            standings,
+        })
+    }
+}
+
+// A tab, with its rows in the default order, which sorting and searching
+// only change the view of
+struct View {
+    title: &'static str,
+    headers: Vec<String>,
+    rows: Vec<ViewRow>,
+    sort: Option<Sort>,
+    column: usize,
+    state: TableState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Sort {
+    column: usize,
+    descending: bool,
+}
+
+struct ViewRow {
+    cells: Vec<Value>,
+    // Shown in the detail pane while the row is selected
+    detail: Vec<Line<'static>>,
+}
+
+pub struct App {
+    title: String,
+    views: Vec<View>,
+    tab: usize,
+    search: String,
+    searching: bool,
+    quit: bool,
+}
+
+impl App {
+    pub fn new(dashboard: Dashboard) -> Self {
+        let players = &dashboard.players;
+        let teams = TopTeams::new(dashboard.teams)
+            .sort_by(TeamColumn::GoalsScored)
+            .collect::<Vec<_>>();
+
+        let mut views = vec![
+            player_view("Top Scorers", Leaderboard::Goals, players),
+            player_view("Assists", Leaderboard::Assists, players),
+            player_view("Combined", Leaderboard::Players, players),
+            View::new("Teams", cmd::top_teams(teams)),
+        ];
+        if let Some(table) = dashboard
+            .standings
+            .and_then(|standings| cmd::league_table(standings, false).pop())
+        {
+            views.push(View::new("Standings", table));
+        }
+
+        Self {
+            title: format!(" matchday {} ", dashboard.season),
+            views,
+            tab: 0,
+            search: String::new(),
+            searching: false,
+            quit: false,
+        }
+    }
+
+    // Until quit, restoring the terminal even if drawing failed
+    pub fn run(mut self) -> io::Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal);
+        ratatui::restore();
+        result
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.on_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.set_search(String::new());
+                }
+                KeyCode::Backspace => {
+                    let mut search = self.search.clone();
+                    search.pop();
+                    self.set_search(search);
+                }
+                KeyCode::Char(c) => self.set_search(format!("{}{c}", self.search)),
+                _ => self.on_navigation_key(key.code),
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            // Clears the search first
+            KeyCode::Esc if !self.search.is_empty() => self.set_search(String::new()),
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Tab => self.tab = (self.tab + 1) % self.views.len(),
+            KeyCode::BackTab => self.tab = (self.tab + self.views.len() - 1) % self.views.len(),
+            KeyCode::Char(c @ '1'..='9') => {
+                let tab = c as usize - '1' as usize;
+                if tab < self.views.len() {
+                    self.tab = tab;
+                }
+            }
+            KeyCode::Char('s') => self.views[self.tab].toggle_sort(),
+            KeyCode::Char('k') => self.on_navigation_key(KeyCode::Up),
+            KeyCode::Char('j') => self.on_navigation_key(KeyCode::Down),
+            KeyCode::Char('h') => self.on_navigation_key(KeyCode::Left),
+            KeyCode::Char('l') => self.on_navigation_key(KeyCode::Right),
+            code => self.on_navigation_key(code),
+        }
+    }
+
+    fn on_navigation_key(&mut self, code: KeyCode) {
+        let visible = self.visible().len();
+        let view = &mut self.views[self.tab];
+        let selected = view.state.selected().unwrap_or_default();
+        let last = visible.saturating_sub(1);
+
+        let selected = match code {
+            KeyCode::Up => selected.saturating_sub(1),
+            KeyCode::Down => (selected + 1).min(last),
+            KeyCode::PageUp => selected.saturating_sub(10),
+            KeyCode::PageDown => (selected + 10).min(last),
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            KeyCode::Left => {
+                view.column = view.column.saturating_sub(1);
+                selected
+            }
+            KeyCode::Right => {
+                view.column = (view.column + 1).min(view.headers.len() - 1);
+                selected
+            }
+            _ => selected,
+        };
+        view.state.select(Some(selected));
+    }
+
+    // The rows shown are filtered in every tab, so each starts over from the top
+    fn set_search(&mut self, search: String) {
+        self.search = search;
+        for view in &mut self.views {
+            view.state.select(Some(0));
+        }
+    }
+
+    fn visible(&self) -> Vec<&ViewRow> {
+        self.views[self.tab].visible(&self.search)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, main_area, footer_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [table_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)])
+                .areas(main_area);
+
+        let titles = self
+            .views
+            .iter()
+            .enumerate()
+            .map(|(i, view)| format!("{} {}", i + 1, view.title));
+        let tabs = Tabs::new(titles)
+            .select(self.tab)
+            .block(Block::bordered().title(self.title.as_str()))
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+        frame.render_widget(tabs, tabs_area);
+
+        let view = &self.views[self.tab];
+        let rows = view.visible(&self.search);
+        let detail = rows
+            .get(view.state.selected().unwrap_or_default())
+            .map(|row| row.detail.clone())
+            .unwrap_or_default();
+
+        let widths: Vec<Constraint> = (0..view.headers.len())
+            .map(|column| {
+                let width = rows
+                    .iter()
+                    .map(|row| text(&row.cells[column]).chars().count())
+                    .chain([view.headers[column].chars().count() + 2])
+                    .max()
+                    .unwrap_or_default();
+                Constraint::Length(width as u16)
+            })
+            .collect();
+        let header = Row::new(view.headers.iter().enumerate().map(|(column, header)| {
+            let arrow = match view.sort {
+                Some(sort) if sort.column == column && sort.descending => " ▼",
+                Some(sort) if sort.column == column => " ▲",
+                _ => "",
+            };
+            let style = if column == view.column {
+                Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::new().add_modifier(Modifier::BOLD)
+            };
+            Cell::from(format!("{header}{arrow}")).style(style)
+        }));
+        let table = TableWidget::new(
+            rows.iter()
+                .map(|row| Row::new(row.cells.iter().map(|cell| Cell::from(text(cell))))),
+            widths,
+        )
+        .header(header)
+        .block(Block::bordered())
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = view.state.clone();
+        frame.render_stateful_widget(table, table_area, &mut state);
+        *self.views[self.tab].state.offset_mut() = state.offset();
+
+        let detail = Paragraph::new(detail).block(Block::bordered().title(" Details "));
+        frame.render_widget(detail, detail_area);
+
+        let footer = if self.searching || !self.search.is_empty() {
+            let cursor = if self.searching { "█" } else { "" };
+            Paragraph::new(format!("/{}{cursor}", self.search))
+                .block(Block::bordered().title(" Search "))
+        } else {
+            Paragraph::new(HELP).block(Block::bordered())
+        };
+        frame.render_widget(footer, footer_area);
+    }
+}
+
+impl View {
+    // Any other row is shown as its columns, one per line
+    fn new(title: &'static str, table: Table) -> Self {
+        let rows = table
+            .rows
+            .into_iter()
+            .map(|cells| ViewRow {
+                detail: table
+                    .headers
+                    .iter()
+                    .zip(&cells)
+                    .map(|(header, cell)| Line::from(format!("{header}: {}", text(cell))))
+                    .collect(),
+                cells,
+            })
+            .collect();
+        Self {
+            title,
+            headers: table.headers,
+            rows,
+            sort: None,
+            column: 0,
+            state: TableState::new().with_selected(Some(0)),
+        }
+    }
+
+    // Numbers are sorted from the highest, names alphabetically, sorting by
+    // the same column again reverses it
+    fn toggle_sort(&mut self) {
+        self.sort = Some(match self.sort {
+            Some(sort) if sort.column == self.column => Sort {
+                column: self.column,
+                descending: !sort.descending,
+            },
+            _ => Sort {
+                column: self.column,
+                descending: self
+                    .rows
+                    .first()
+                    .is_some_and(|row| row.cells[self.column].is_number()),
+            },
+        });
+        self.state.select(Some(0));
+    }
+
+    // Matching every word of the search in any of the text columns, ties are
+    // kept in the default order
+    fn visible(&self, search: &str) -> Vec<&ViewRow> {
+        let search = search.to_lowercase();
+        let words: Vec<&str> = search
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let mut rows: Vec<&ViewRow> = self
+            .rows
+            .iter()
+            .filter(|row| {
+                let texts: String = row
+                    .cells
+                    .iter()
+                    .filter_map(|cell| cell.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase();
+                words.iter().all(|word| texts.contains(word))
+            })
+            .collect();
+
+        if let Some(sort) = self.sort {
+            rows.sort_by(|a, b| {
+                let ordering = compare_cells(&a.cells[sort.column], &b.cells[sort.column]);
+                if sort.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        rows
+    }
+}
+
+// Every player, ranked by the leaderboard, with their stats against the
+// league's in the detail pane
+fn player_view(title: &'static str, leaderboard: Leaderboard, players: &[SeasonPlayer]) -> View {
+    let mut table = Table::new(&[
+        "Rank",
+        "Player Name",
+        "Team",
+        "Matches",
+        "Goals",
+        "Assists",
+        "Shots",
+        "Cards",
+    ]);
+    let ranked = leaderboard.rank(players.to_vec(), players.len());
+    for (i, player) in ranked.iter().enumerate() {
+        let stats = &player.statistics;
+        table.push(vec![
+            json!(i + 1),
+            json!(player.name),
+            json!(player.team()),
+            json!(stats.matches_played),
+            json!(stats.goals_scored),
+            json!(stats.assists),
+            json!(stats.shots_on_target + stats.shots_off_target + stats.shots_blocked),
+            json!(stats.yellow_cards + stats.yellow_red_cards + stats.red_cards),
+        ]);
+    }
+
+    let mut view = View::new(title, table);
+    for (row, player) in view.rows.iter_mut().zip(&ranked) {
+        row.detail = player_detail(player, players);
+    }
+    view
+}
+
+fn player_detail(player: &SeasonPlayer, league: &[SeasonPlayer]) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::styled(
+            player.name.clone(),
+            Style::new().add_modifier(Modifier::BOLD),
+        ),
+        Line::from(player.team()),
+        Line::from(""),
+    ];
+    for row in compare(std::slice::from_ref(player), league) {
+        let compared = &row.values[0];
+        let value = match compared.value {
+            Some(value) if row.per_match => format!("{value:.2}"),
+            Some(value) => format!("{value}"),
+            None => "-".into(),
+        };
+        let percentile = compared
+            .percentile
+            .map(|percentile| format!(" (p{percentile})"))
+            .unwrap_or_default();
+        lines.push(Line::from(format!("{:<20}{value}{percentile}", row.label)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("(pN) percentile rank within the league"));
+    lines
+}
+
+fn compare_cells(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => text(a).cmp(&text(b)),
+    }
+}
+
+fn text(cell: &Value) -> String {
+    match cell {
+        Value::String(text) => text.clone(),
+        Value::Null => "-".into(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    use ratatui::Terminal;
+
+    use crate::players::SeasonPlayer;
+    use crate::tui::{App, Dashboard};
+    use crate::types::PlayerStats;
+
+    fn mock_app() -> App {
+        let players = [
+            ("Haaland, Erling", "MCI", 27, 5),
+            ("Palmer, Cole", "CHE", 22, 11),
+            ("Watkins, Ollie", "AVL", 19, 13),
+        ]
+        .into_iter()
+        .map(|(name, team, goals, assists)| SeasonPlayer {
+            id: name.into(),
+            name: name.into(),
+            teams: vec![team.into()],
+            statistics: PlayerStats {
+                goals_scored: goals,
+                assists,
+                matches_played: 34,
+                ..Default::default()
+            },
+        })
+        .collect();
+        App::new(Dashboard {
+            season: "23/24".into(),
+            players,
+            teams: vec![],
+            standings: None,
+        })
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.on_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    fn names(app: &App) -> Vec<String> {
+        app.visible()
+            .iter()
+            .map(|row| row.cells[1].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_tabs_and_sorting() {
+        let mut app = mock_app();
+        assert_eq!(
+            names(&app),
+            ["Haaland, Erling", "Palmer, Cole", "Watkins, Ollie"]
+        );
+
+        // the assists tab
+        press(&mut app, "2");
+        assert_eq!(
+            names(&app),
+            ["Watkins, Ollie", "Palmer, Cole", "Haaland, Erling"]
+        );
+
+        // by name, then reversed
+        app.on_key(KeyEvent::from(KeyCode::Right));
+        press(&mut app, "s");
+        assert_eq!(
+            names(&app),
+            ["Haaland, Erling", "Palmer, Cole", "Watkins, Ollie"]
+        );
+        press(&mut app, "s");
+        assert_eq!(
+            names(&app),
+            ["Watkins, Ollie", "Palmer, Cole", "Haaland, Erling"]
+        );
+
+        app.on_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.tab, 2);
+    }
+
+    #[test]
+    fn test_search_and_detail() {
+        let mut app = mock_app();
+        press(&mut app, "/cole palmer");
+        assert_eq!(names(&app), ["Palmer, Cole"]);
+        app.on_key(KeyEvent::from(KeyCode::Enter));
+        assert!(!app.searching);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Palmer, Cole"));
+        assert!(!screen.contains("Watkins, Ollie"));
+        assert!(screen.contains("Goals               22 (p66)"));
+
+        // clears the search before quitting
+        app.on_key(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(names(&app).len(), 3);
+        assert!(!app.quit);
+        press(&mut app, "q");
+        assert!(app.quit);
+    }
+}
@@ -1,5 +1,21 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Season {
+    pub id: String,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    // Such as "23/24"
+    pub year: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompetitionSeasons {
+    pub seasons: Vec<Season>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SeasonCompetitors {
     pub season_competitors: Vec<SeasonCompetitor>,
@@ -10,10 +26,27 @@ pub struct SeasonCompetitor {
     pub id: String,
 }
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+// The API omits the stats that are zero, and never reports the minutes played
+#[derive(Clone, Default, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PlayerStats {
     pub assists: usize,
     pub goals_scored: usize,
+    pub matches_played: usize,
+    pub goals_by_head: usize,
+    pub goals_by_penalty: usize,
+    pub penalties_missed: usize,
+    pub own_goals: usize,
+    pub shots_on_target: usize,
+    pub shots_off_target: usize,
+    pub shots_blocked: usize,
+    pub corner_kicks: usize,
+    pub offsides: usize,
+    pub yellow_cards: usize,
+    pub yellow_red_cards: usize,
+    pub red_cards: usize,
+    pub substituted_in: usize,
+    pub substituted_out: usize,
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
@@ -23,12 +56,163 @@ pub struct Player {
     pub statistics: PlayerStats,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
-pub struct CompetitorPlayers {
+// Team aggregates for the whole season, the API omits some of them when they're zero
+#[derive(Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamStats {
+    pub matches_played: usize,
+    pub average_ball_possession: f64,
+    pub goals_scored: usize,
+    pub goals_scored_first_half: usize,
+    pub goals_scored_second_half: usize,
+    pub goals_conceded: usize,
+    pub goals_conceded_first_half: usize,
+    pub goals_conceded_second_half: usize,
+    pub goals_by_foot: usize,
+    pub goals_by_head: usize,
+    pub shots_total: usize,
+    pub shots_on_target: usize,
+    pub shots_off_target: usize,
+    pub shots_blocked: usize,
+    pub corner_kicks: usize,
+    pub cards_given: usize,
+    pub yellow_cards: usize,
+    pub yellow_red_cards: usize,
+    pub red_cards: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Competitor {
+    pub id: String,
+    pub name: String,
+    pub abbreviation: String,
+    pub statistics: TeamStats,
     pub players: Vec<Player>,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct CompetitorStats {
-    pub competitor: CompetitorPlayers,
+    pub competitor: Competitor,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompetitorRef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub abbreviation: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StandingType {
+    Total,
+    Home,
+    Away,
+    // Cups and other competitions may have more types, these are not displayed
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandingRow {
+    pub rank: usize,
+    pub competitor: CompetitorRef,
+    pub played: usize,
+    pub win: usize,
+    pub draw: usize,
+    pub loss: usize,
+    pub goals_for: usize,
+    pub goals_against: usize,
+    pub goals_diff: isize,
+    pub points: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandingGroup {
+    pub standings: Vec<StandingRow>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    #[serde(rename = "type")]
+    pub kind: StandingType,
+    pub groups: Vec<StandingGroup>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonStandings {
+    pub standings: Vec<Standing>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Qualifier {
+    Home,
+    Away,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchCompetitor {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub abbreviation: String,
+    pub qualifier: Option<Qualifier>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Round {
+    pub number: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SportEventContext {
+    pub round: Option<Round>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SportEvent {
+    pub id: String,
+    pub start_time: DateTime<Utc>,
+    #[serde(default)]
+    pub sport_event_context: SportEventContext,
+    pub competitors: Vec<MatchCompetitor>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStatus {
+    NotStarted,
+    Live,
+    Ended,
+    Closed,
+    Postponed,
+    Delayed,
+    Interrupted,
+    Suspended,
+    Cancelled,
+    Abandoned,
+    #[serde(other)]
+    #[cfg_attr(feature = "cli", value(skip))]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SportEventStatus {
+    pub status: MatchStatus,
+    pub home_score: Option<usize>,
+    pub away_score: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchSummary {
+    pub sport_event: SportEvent,
+    pub sport_event_status: SportEventStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonSchedules {
+    pub schedules: Vec<MatchSummary>,
 }
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use crate::players::SeasonPlayer;
+
+// How a player moved in a leaderboard since the previous refresh
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    Up(usize),
+    Down(usize),
+    Same,
+    // Wasn't ranked before
+    New,
+}
+
+// A ranked player with what changed since the previous refresh
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiveEntry {
+    pub rank: usize,
+    pub movement: Movement,
+    pub new_goals: usize,
+    pub new_assists: usize,
+    pub player: SeasonPlayer,
+}
+
+// Compares each refresh of a leaderboard with the previous one. The stats
+// of every player are kept, not only of the ranked ones, so the goals that
+// got a player into the leaderboard show up too.
+#[derive(Default)]
+pub struct LiveLeaderboard {
+    previous: Option<Refresh>,
+}
+
+// By player ID and team, as players are listed by club if split by team
+pub(crate) type PlayerKey = (String, String);
+
+struct Refresh {
+    ranks: HashMap<PlayerKey, usize>,
+    stats: HashMap<PlayerKey, (usize, usize)>,
+}
+
+impl LiveLeaderboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The ranked players are the top ones of all the players. Nothing moved
+    // on the first refresh, as there's nothing to compare it with.
+    pub fn update(
+        &mut self,
+        players: &[SeasonPlayer],
+        ranked: Vec<SeasonPlayer>,
+    ) -> Vec<LiveEntry> {
+        let entries = ranked
+            .into_iter()
+            .enumerate()
+            .map(|(i, player)| {
+                let rank = i + 1;
+                let key = key(&player);
+                let (movement, (goals, assists)) = match &self.previous {
+                    None => (
+                        Movement::Same,
+                        (player.statistics.goals_scored, player.statistics.assists),
+                    ),
+                    Some(previous) => (
+                        match previous.ranks.get(&key) {
+                            Some(&was) if was > rank => Movement::Up(was - rank),
+                            Some(&was) if was < rank => Movement::Down(rank - was),
+                            Some(_) => Movement::Same,
+                            None => Movement::New,
+                        },
+                        previous.stats.get(&key).copied().unwrap_or_default(),
+                    ),
+                };
+                LiveEntry {
+                    rank,
+                    movement,
+                    new_goals: player.statistics.goals_scored.saturating_sub(goals),
+                    new_assists: player.statistics.assists.saturating_sub(assists),
+                    player,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.previous = Some(Refresh {
+            ranks: entries
+                .iter()
+                .map(|entry| (key(&entry.player), entry.rank))
+                .collect(),
+            stats: players
+                .iter()
+                .map(|player| {
+                    let stats = &player.statistics;
+                    (key(player), (stats.goals_scored, stats.assists))
+                })
+                .collect(),
+        });
+        entries
+    }
+}
+
+pub(crate) fn key(player: &SeasonPlayer) -> PlayerKey {
+    (player.id.clone(), player.team())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::players::SeasonPlayer;
+    use crate::top_players::TopPlayers;
+    use crate::types::PlayerStats;
+    use crate::watch::{LiveLeaderboard, Movement};
+
+    fn mock_players(goals: &[(&str, usize)]) -> Vec<SeasonPlayer> {
+        goals
+            .iter()
+            .map(|(id, goals)| SeasonPlayer {
+                id: id.to_string(),
+                name: id.to_string(),
+                teams: vec!["MCI".into()],
+                statistics: PlayerStats {
+                    goals_scored: *goals,
+                    ..Default::default()
+                },
+            })
+            .collect()
+    }
+
+    fn update(
+        leaderboard: &mut LiveLeaderboard,
+        goals: &[(&str, usize)],
+    ) -> Vec<(String, Movement, usize)> {
+        let players = mock_players(goals);
+        let top = TopPlayers::new(players.clone(), 2).by_goals().collect();
+        leaderboard
+            .update(&players, top)
+            .into_iter()
+            .map(|entry| (entry.player.id, entry.movement, entry.new_goals))
+            .collect()
+    }
+
+    #[test]
+    fn test_update() {
+        let mut leaderboard = LiveLeaderboard::new();
+        assert_eq!(
+            update(
+                &mut leaderboard,
+                &[("haaland", 25), ("palmer", 22), ("saka", 21)]
+            ),
+            [
+                ("haaland".into(), Movement::Same, 0),
+                ("palmer".into(), Movement::Same, 0)
+            ]
+        );
+
+        // saka scored twice, getting into the leaderboard
+        assert_eq!(
+            update(
+                &mut leaderboard,
+                &[("haaland", 25), ("palmer", 22), ("saka", 23)]
+            ),
+            [
+                ("haaland".into(), Movement::Same, 0),
+                ("saka".into(), Movement::New, 2)
+            ]
+        );
+
+        assert_eq!(
+            update(
+                &mut leaderboard,
+                &[("haaland", 25), ("palmer", 22), ("saka", 26)]
+            ),
+            [
+                ("saka".into(), Movement::Up(1), 3),
+                ("haaland".into(), Movement::Down(1), 0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use matchday::{Client, Error, SportsApiClient};
+
+const SEASONS: &str = r#"{ "seasons": [] }"#;
+
+#[tokio::test]
+async fn test_builder_against_stub_server() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock(
+            "GET",
+            "/stub/soccer/trial/v4/es/competitions/sr:competition:17/seasons.json",
+        )
+        .match_header("x-api-key", "asdf1234")
+        .match_header("user-agent", "stats-bot/1.0")
+        .with_header("content-type", "application/json")
+        .with_body(SEASONS)
+        .create_async()
+        .await;
+
+    let client = SportsApiClient::builder()
+        .api_key("asdf1234")
+        .base_url(format!("{}/stub/", server.url()).parse().unwrap())
+        .locale("es")
+        .user_agent("stats-bot/1.0")
+        .build()
+        .unwrap();
+
+    let seasons = client.fetch_seasons().await.unwrap();
+    mock.assert_async().await;
+    assert!(seasons.seasons.is_empty());
+}
+
+#[tokio::test]
+async fn test_builder_with_custom_client() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock(
+            "GET",
+            "/soccer/trial/v4/en/competitions/sr:competition:17/seasons.json",
+        )
+        .match_header("x-api-key", "asdf1234")
+        .match_header("user-agent", "custom")
+        .with_body(SEASONS)
+        .create_async()
+        .await;
+
+    let client = SportsApiClient::builder()
+        .api_key("asdf1234")
+        .base_url(server.url().parse().unwrap())
+        .client(
+            reqwest::Client::builder()
+                .user_agent("custom")
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+
+    client.fetch_seasons().await.unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_builder_timeout() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", mockito::Matcher::Any)
+        .with_chunked_body(|w| {
+            std::thread::sleep(Duration::from_millis(500));
+            w.write_all(SEASONS.as_bytes())
+        })
+        .create_async()
+        .await;
+
+    let client = SportsApiClient::builder()
+        .api_key("asdf1234")
+        .base_url(server.url().parse().unwrap())
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let err = client.fetch_seasons().await.unwrap_err();
+    assert!(matches!(err, Error::Request(e) if e.is_timeout()));
+}
+
+#[test]
+fn test_builder_requires_api_key() {
+    assert!(matches!(
+        SportsApiClient::builder().build(),
+        Err(Error::MissingApiKey)
+    ));
+}
@@ -0,0 +1,447 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::Parser;
+
+use matchday::mock_server::{MockServer, MockServerOptions};
+use matchday::{Cli, Config, Settings, Source};
+
+// Runs the command line against the mock server in an empty cache dir,
+// returning what it printed
+async fn run(server: &MockServer, cache_folder: &str, args: &[&str]) -> anyhow::Result<String> {
+    let cache_dir = cache_dir(cache_folder);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let result = run_cached(server, &cache_dir, args).await;
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    result
+}
+
+// Same, keeping what was cached before and after
+async fn run_cached(
+    server: &MockServer,
+    cache_dir: &Path,
+    args: &[&str],
+) -> anyhow::Result<String> {
+    let mut argv = vec!["matchday"];
+    argv.extend(args);
+    let cli = Cli::try_parse_from(argv)?;
+    // The flags of the command line still override these
+    let settings = Settings {
+        api_key: Some("asdf1234".into()),
+        base_url: Some(server.url()),
+        cache_dir: Some(cache_dir.to_path_buf()),
+        rate_limit: Some(0.0),
+        ..Default::default()
+    };
+    let config = Config::from_layers(vec![
+        (settings, Source::Default),
+        (cli.settings, Source::Cli),
+    ])?;
+
+    let mut out = Vec::new();
+    cli.cmd.run(config, &mut out).await?;
+
+    Ok(String::from_utf8(out)?)
+}
+
+fn cache_dir(folder: &str) -> PathBuf {
+    std::env::temp_dir().join(folder)
+}
+
+#[tokio::test]
+async fn test_top_goals() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+
+    let out = run(
+        &server,
+        "matchday-e2e-top-goals",
+        &["top", "goals", "--limit", "3"],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        out,
+        "Goals | Player Name | Team\n 27 | Haaland, Erling | MCI\n 22 | Palmer, Cole | CHE\n 16 | Saka, Bukayo | ARS\n"
+    );
+    // the seasons, the competitors and the stats of each team
+    assert_eq!(server.requests(), 4);
+}
+
+#[tokio::test]
+async fn test_table_as_json() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+
+    let out = run(
+        &server,
+        "matchday-e2e-table",
+        &["table", "--format", "json"],
+    )
+    .await
+    .unwrap();
+
+    let table: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let teams: Vec<&str> = table
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["team_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(teams, vec!["Manchester City", "Arsenal FC", "Chelsea FC"]);
+}
+
+#[tokio::test]
+async fn test_retries_rate_limited_and_failed_requests() {
+    let server = MockServer::start(MockServerOptions {
+        latency: Duration::from_millis(10),
+        // never more than two failures in a row, so three retries are enough
+        rate_limit_every: Some(3),
+        server_error_every: Some(5),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let out = run(
+        &server,
+        "matchday-e2e-retries",
+        &["compare", "palmer", "saka"],
+    )
+    .await
+    .unwrap();
+
+    assert!(out.starts_with("Stat | Palmer, Cole | Saka, Bukayo\n"));
+    assert!(out.contains(" Goals | 22* (p83) | 16 (p66)\n"), "{out}");
+    assert!(server.requests() > 5);
+}
+
+#[tokio::test]
+async fn test_metrics_file() {
+    let server = MockServer::start(MockServerOptions {
+        rate_limit_every: Some(2),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let metrics_file = std::env::temp_dir().join("matchday-e2e-metrics.prom");
+    let _ = std::fs::remove_file(&metrics_file);
+
+    run(
+        &server,
+        "matchday-e2e-metrics",
+        &[
+            "top-goals",
+            "--metrics-file",
+            metrics_file.to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    // every other request is rate limited, the stats of each team are retried once
+    let metrics = std::fs::read_to_string(&metrics_file).unwrap();
+    for line in [
+        r#"matchday_api_requests_total{endpoint="competitors",status="200"} 1"#,
+        r#"matchday_api_requests_total{endpoint="competitor_stats",status="200"} 3"#,
+        r#"matchday_api_requests_total{endpoint="competitor_stats",status="429"} 3"#,
+        r#"matchday_api_retries_total{endpoint="competitor_stats"} 3"#,
+        r#"matchday_cache_misses_total{endpoint="competitor_stats"} 3"#,
+        r#"matchday_cache_writes_total{endpoint="competitor_stats"} 3"#,
+        r#"matchday_api_request_duration_seconds_count{endpoint="competitor_stats"} 6"#,
+    ] {
+        assert!(metrics.contains(line), "missing {line} in\n{metrics}");
+    }
+
+    let _ = std::fs::remove_file(&metrics_file);
+}
+
+#[tokio::test]
+async fn test_warm_cache_within_quota_budget() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+
+    // refused before any call, as 20 teams are expected until they're cached
+    let err = run(
+        &server,
+        "matchday-e2e-warm-cache",
+        &["cache", "warm", "--quota-budget", "5"],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "warming the cache needs 24 API calls, but only 5 are left in this month's budget, use --force to warm it anyway"
+    );
+    assert_eq!(server.requests(), 0);
+
+    // the seasons, competitors, the stats of 3 teams, standings and schedules
+    run(
+        &server,
+        "matchday-e2e-warm-cache",
+        &["cache", "warm", "--quota-budget", "5", "--force"],
+    )
+    .await
+    .unwrap();
+    assert_eq!(server.requests(), 7);
+}
+
+#[tokio::test]
+async fn test_old_command_names() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+
+    for (old, new) in [
+        (&["top-goals"][..], &["top", "goals"][..]),
+        (
+            &["teams", "--sort-by", "shots"],
+            &["team", "--sort-by", "shots"],
+        ),
+        (
+            &["history", "saka", "--seasons", "23/24"],
+            &["player", "saka"],
+        ),
+    ] {
+        let old_out = run(&server, "matchday-e2e-old-names", old).await.unwrap();
+        let new_out = run(&server, "matchday-e2e-new-names", new).await.unwrap();
+        assert_eq!(old_out, new_out, "{old:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_team_and_cache_status() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+
+    let out = run(
+        &server,
+        "matchday-e2e-team",
+        &["team", "mci", "--format", "json"],
+    )
+    .await
+    .unwrap();
+    let teams: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(teams[0]["team_name"], "Manchester City");
+    assert_eq!(teams.as_array().unwrap().len(), 1);
+    let err = run(&server, "matchday-e2e-team", &["team", "liv"])
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "no team matches \"liv\"");
+
+    // nothing is cached, so --offline makes no request
+    let requests = server.requests();
+    let out = run(&server, "matchday-e2e-cache-status", &["cache", "status"])
+        .await
+        .unwrap();
+    assert!(out.starts_with("Season | Year | Teams |"), "{out}");
+    let err = run(
+        &server,
+        "matchday-e2e-offline",
+        &["--offline", "top", "players"],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no cached competitors of sr:season:105353, fetch them without --offline first"
+    );
+    assert_eq!(server.requests(), requests);
+}
+
+#[tokio::test]
+async fn test_offline_unfinished_fixtures() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let cache_dir = cache_dir("matchday-e2e-offline-fixtures");
+    let _ = std::fs::remove_dir_all(&cache_dir);
+
+    let online = run_cached(&server, &cache_dir, &["fixtures", "--format", "json"])
+        .await
+        .unwrap();
+    let requests = server.requests();
+
+    // a match not played yet, which would be fetched again online
+    let schedules_file = cache_dir.join("en/sr:season:105353/schedules.json");
+    let schedules = std::fs::read_to_string(&schedules_file).unwrap();
+    let schedules = schedules.replacen("\"closed\"", "\"not_started\"", 1);
+    std::fs::write(&schedules_file, schedules).unwrap();
+
+    let offline = run_cached(
+        &server,
+        &cache_dir,
+        &["--offline", "fixtures", "--format", "json"],
+    )
+    .await;
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let offline: serde_json::Value = serde_json::from_str(&offline.unwrap()).unwrap();
+    let online: serde_json::Value = serde_json::from_str(&online).unwrap();
+    assert_eq!(
+        offline.as_array().unwrap().len(),
+        online.as_array().unwrap().len()
+    );
+    assert_eq!(server.requests(), requests);
+}
+
+#[tokio::test]
+async fn test_db_sync_and_query() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    // outside the cache dir, which is removed after each run
+    let db_file = std::env::temp_dir().join("matchday-e2e.db");
+    let _ = std::fs::remove_file(&db_file);
+    let db_file = db_file.to_str().unwrap();
+
+    run(
+        &server,
+        "matchday-e2e-db",
+        &["db", "sync", "--db-file", db_file],
+    )
+    .await
+    .unwrap();
+    // the seasons, the competitors and the stats of each team
+    assert_eq!(server.requests(), 5);
+
+    let out = run(
+        &server,
+        "matchday-e2e-db",
+        &[
+            "query",
+            "SELECT p.name, c.abbreviation, s.goals_scored FROM player_season_stats s \
+             JOIN players p ON p.id = s.player_id \
+             JOIN competitors c ON c.id = s.competitor_id \
+             ORDER BY s.goals_scored DESC LIMIT 2",
+            "--db-file",
+            db_file,
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        out,
+        "name | abbreviation | goals_scored\n Haaland, Erling | MCI | 27\n Palmer, Cole | CHE | 22\n"
+    );
+    assert_eq!(server.requests(), 5);
+
+    let _ = std::fs::remove_file(db_file);
+}
+
+#[cfg(feature = "export")]
+#[tokio::test]
+async fn test_export() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let out = std::env::temp_dir().join("matchday-e2e-export.parquet");
+
+    run(
+        &server,
+        "matchday-e2e-export",
+        &["export", "--out", out.to_str().unwrap()],
+    )
+    .await
+    .unwrap();
+
+    let file = std::fs::File::open(&out).unwrap();
+    let metadata = parquet::file::reader::FileReader::metadata(
+        &parquet::file::serialized_reader::SerializedFileReader::new(file).unwrap(),
+    )
+    .file_metadata()
+    .clone();
+    let _ = std::fs::remove_file(&out);
+    assert_eq!(metadata.num_rows(), 6);
+
+    let err = run(
+        &server,
+        "matchday-e2e-export",
+        &["top-goals", "--format", "parquet"],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the parquet format is only written by export"
+    );
+}
+
+#[tokio::test]
+async fn test_fails_once_out_of_retries() {
+    let server = MockServer::start(MockServerOptions {
+        server_error_every: Some(1),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let err = run(
+        &server,
+        "matchday-e2e-failure",
+        &["table", "--retries", "0"],
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.to_string().contains("503 Service Unavailable"), "{err}");
+}
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let cassette = std::env::temp_dir().join("matchday-e2e-cassette.json");
+    let _ = std::fs::remove_file(&cassette);
+    let cassette_arg = cassette.to_str().unwrap();
+
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let recorded = run(
+        &server,
+        "matchday-e2e-record",
+        &["top-players", "--record", cassette_arg],
+    )
+    .await
+    .unwrap();
+    let requests = server.requests();
+    assert!(!std::fs::read_to_string(&cassette)
+        .unwrap()
+        .contains("asdf1234"));
+
+    // Served from the cassette, with an empty cache
+    let replayed = run(
+        &server,
+        "matchday-e2e-replay",
+        &["top-players", "--replay", cassette_arg],
+    )
+    .await
+    .unwrap();
+    assert_eq!(replayed, recorded);
+    assert_eq!(server.requests(), requests);
+
+    let _ = std::fs::remove_file(&cassette);
+}
+
+#[tokio::test]
+async fn test_completions_and_man_page() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+
+    let script = run(
+        &server,
+        "matchday-e2e-completions",
+        &["completions", "fish"],
+    )
+    .await
+    .unwrap();
+    assert!(script.contains("COMPLETE=fish"), "{script}");
+    let man = run(&server, "matchday-e2e-man", &["man"]).await.unwrap();
+    assert!(man.starts_with(".ie"), "{man}");
+    assert!(man.contains("matchday\\-completions"), "{man}");
+    assert_eq!(server.requests(), 0);
+}
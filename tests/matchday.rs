@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use matchday::mock_server::{MockServer, MockServerOptions};
+use matchday::{Config, Matchday, Settings, Source, StandingType};
+
+fn config(server: &MockServer, cache_folder: &str) -> Config {
+    let cache_dir = cache_dir(cache_folder);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+
+    let settings = Settings {
+        api_key: Some("asdf1234".into()),
+        base_url: Some(server.url()),
+        cache_dir: Some(cache_dir),
+        rate_limit: Some(0.0),
+        ..Default::default()
+    };
+    Config::from_layers(vec![(settings, Source::Default)]).unwrap()
+}
+
+fn cache_dir(folder: &str) -> PathBuf {
+    std::env::temp_dir().join(folder)
+}
+
+#[tokio::test]
+async fn test_top_scorers() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let config = config(&server, "matchday-lib-top-scorers");
+    let matchday = Matchday::new(&config).unwrap();
+
+    let top = matchday.top_scorers("23/24", 2).await.unwrap();
+    let scorers: Vec<(&str, usize)> = top
+        .iter()
+        .map(|p| (p.name.as_str(), p.statistics.goals_scored))
+        .collect();
+    assert_eq!(scorers, [("Haaland, Erling", 27), ("Palmer, Cole", 22)]);
+
+    // served from the cache after the first time
+    let requests = server.requests();
+    matchday.top_scorers("sr:season:105353", 2).await.unwrap();
+    assert_eq!(server.requests(), requests);
+
+    let (teams, players) = matchday.teams_and_players("23/24", false).await.unwrap();
+    assert_eq!(teams.len(), 3);
+    assert_eq!(players, matchday.players("23/24", false).await.unwrap());
+
+    matchday.clear_cache().unwrap();
+}
+
+#[tokio::test]
+async fn test_standings() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let config = config(&server, "matchday-lib-standings");
+    let matchday = Matchday::new(&config).unwrap();
+
+    let standings = matchday.standings("23/24").await.unwrap();
+    let leader = standings.rows(StandingType::Total).next().unwrap();
+    assert_eq!(leader.competitor.abbreviation, "MCI");
+    assert_eq!(leader.points, 91);
+
+    matchday.clear_cache().unwrap();
+}
+
+#[tokio::test]
+async fn test_quota() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let config = config(&server, "matchday-lib-quota");
+    let matchday = Matchday::new(&config).unwrap();
+
+    matchday.top_scorers("23/24", 2).await.unwrap();
+    // kept by clearing the cache
+    matchday.clear_cache().unwrap();
+    let usage = matchday.quota().unwrap().unwrap();
+    // the seasons, the competitors and the stats of each team
+    assert_eq!(usage.calls(), 5);
+    assert_eq!(usage.endpoints["competitor_stats"], 3);
+    assert_eq!(usage.remaining(), None);
+
+    let _ = std::fs::remove_dir_all(&config.cache_dir);
+}
+
+#[tokio::test]
+async fn test_offline() {
+    let server = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let config = config(&server, "matchday-lib-offline");
+
+    let err = Matchday::offline(&config)
+        .top_scorers("23/24", 2)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no cached seasons, fetch them without --offline first"
+    );
+
+    Matchday::new(&config)
+        .unwrap()
+        .top_scorers("23/24", 2)
+        .await
+        .unwrap();
+    let requests = server.requests();
+
+    // read from the cache even once it expired, without any request
+    let mut expired = config.clone();
+    expired.cache_ttl = Some(std::time::Duration::ZERO);
+    let offline = Matchday::offline(&expired);
+    let top = offline.top_scorers("23/24", 2).await.unwrap();
+    assert_eq!(top[0].name, "Haaland, Erling");
+    assert!(offline.standings("23/24").await.is_err());
+    assert_eq!(server.requests(), requests);
+
+    offline.clear_cache().unwrap();
+}
@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use matchday::mock_server::{MockServer, MockServerOptions};
+use matchday::{Config, Matchday, Server, ServerOptions, Settings, Source};
+
+// Serves matchday backed by the mock server as the upstream API
+async fn serve(upstream: &MockServer, cache_folder: &str, refresh: Option<Duration>) -> Server {
+    let cache_dir = std::env::temp_dir().join(cache_folder);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+
+    start(config(upstream, cache_dir), refresh).await
+}
+
+async fn start(config: Config, refresh: Option<Duration>) -> Server {
+    let options = ServerOptions {
+        season: config.season.clone(),
+        limit: 10,
+        refresh,
+    };
+    Server::start(Matchday::new(&config).unwrap(), options)
+        .await
+        .unwrap()
+}
+
+fn config(upstream: &MockServer, cache_dir: PathBuf) -> Config {
+    let settings = Settings {
+        api_key: Some("asdf1234".into()),
+        base_url: Some(upstream.url()),
+        cache_dir: Some(cache_dir),
+        rate_limit: Some(0.0),
+        retries: Some(0),
+        ..Default::default()
+    };
+    Config::from_layers(vec![(settings, Source::Default)]).unwrap()
+}
+
+async fn get(server: &Server, path: &str) -> (u16, Value) {
+    let response = reqwest::get(format!("{}{path}", server.url()))
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_endpoints() {
+    let upstream = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let server = serve(&upstream, "matchday-serve-endpoints", None).await;
+
+    let (status, health) = get(&server, "/health").await;
+    assert_eq!((status, health["status"].as_str()), (200, Some("ok")));
+
+    let (status, top) = get(&server, "/seasons/sr:season:105353/top/goals?limit=2").await;
+    assert_eq!(status, 200);
+    assert_eq!(top.as_array().unwrap().len(), 2);
+    assert_eq!(top[0]["rank"], 1);
+    assert_eq!(top[0]["name"], "Haaland, Erling");
+    assert_eq!(top[1]["name"], "Palmer, Cole");
+    assert_eq!(top[1]["statistics"]["goals_scored"], 22);
+
+    // by year, with the slash escaped
+    let (status, top) = get(&server, "/seasons/23%2F24/top/players").await;
+    assert_eq!(status, 200);
+    assert_eq!(top.as_array().unwrap().len(), 6);
+
+    let (status, teams) = get(&server, "/teams").await;
+    assert_eq!(status, 200);
+    assert_eq!(teams.as_array().unwrap().len(), 3);
+
+    let (status, player) = get(&server, "/players/cole%20palmer").await;
+    assert_eq!((status, player["teams"][0].as_str()), (200, Some("CHE")));
+
+    let (status, error) = get(&server, "/players/nobody").await;
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "no player matches \"nobody\"");
+
+    let (status, _) = get(&server, "/seasons/sr:season:105353/top/saves").await;
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn test_upstream_errors() {
+    let upstream = MockServer::start(MockServerOptions {
+        server_error_every: Some(1),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let server = serve(&upstream, "matchday-serve-errors", None).await;
+
+    let (status, error) = get(&server, "/teams").await;
+    assert_eq!(status, 502);
+    // without the upstream URL
+    assert_eq!(error["error"], "failed to fetch the data");
+
+    let (status, _) = get(&server, "/health").await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn test_background_refresh() {
+    let upstream = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let server = serve(
+        &upstream,
+        "matchday-serve-refresh",
+        Some(Duration::from_secs(60 * 60)),
+    )
+    .await;
+
+    // seasons, competitors, the stats of 3 teams, standings and schedules
+    for _ in 0..100 {
+        if upstream.requests() >= 7 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(upstream.requests(), 7);
+
+    // served from the warm cache
+    let (status, _) = get(&server, "/seasons/23%2F24/top/assists").await;
+    assert_eq!(status, 200);
+    assert_eq!(upstream.requests(), 7);
+
+    let metrics = reqwest::get(format!("{}/metrics", server.url()))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics
+        .contains(r#"matchday_api_requests_total{endpoint="competitor_stats",status="200"} 3"#));
+    assert!(metrics.contains(r#"matchday_cache_hits_total{endpoint="competitor_stats"} 3"#));
+}
+
+#[tokio::test]
+async fn test_background_refresh_of_stale_data() {
+    let upstream = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let every = Some(Duration::from_secs(60 * 60));
+    let server = serve(&upstream, "matchday-serve-stale", every).await;
+    for _ in 0..100 {
+        if upstream.requests() >= 7 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(upstream.requests(), 7);
+    drop(server);
+
+    // nothing is older than the interval, restarting fetches nothing
+    let cache_dir = std::env::temp_dir().join("matchday-serve-stale");
+    let _server = start(config(&upstream, cache_dir.clone()), every).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(upstream.requests(), 7);
+
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}
+
+#[tokio::test]
+async fn test_background_refresh_over_budget() {
+    let upstream = MockServer::start(MockServerOptions::default())
+        .await
+        .unwrap();
+    let cache_dir = std::env::temp_dir().join("matchday-serve-budget");
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let mut config = config(&upstream, cache_dir.clone());
+    config.quota_budget = Some(5);
+
+    // a season takes more calls than the budget, the refresh is skipped
+    let _server = start(config, Some(Duration::from_secs(60 * 60))).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(upstream.requests(), 0);
+
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}